@@ -1,22 +1,876 @@
+use crate::error::Error;
+use crate::parsing::character_sets::{is_atext, UTF8_HEADERS};
 use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+use std::net::{Ipv4Addr, Ipv6Addr};
 
-#[derive(Debug, Clone)]
+/// Joins the words of a phrase with the whitespaces that separated them.\
+/// Words are already unquoted and adjacent encoded words merged.
+/// If the separators are missing, as in a phrase built by hand, words are separated with a space.\
+/// [Learn more](https://tools.ietf.org/html/rfc5322#section-3.2.5)
+fn join_phrase<'a>(words: &[Cow<'a, str>], separators: &[Cow<'a, str>]) -> Cow<'a, str> {
+    if let [word] = words {
+        return word.clone();
+    }
+    let mut phrase = String::new();
+    for (idx, word) in words.iter().enumerate() {
+        if idx > 0 {
+            match separators.get(idx - 1) {
+                Some(separator) if separators.len() + 1 == words.len() => {
+                    phrase.push_str(separator)
+                }
+                _ => phrase.push(' '),
+            }
+        }
+        phrase.push_str(word);
+    }
+    Cow::Owned(phrase)
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Mailbox<'a> {
     /// The name associated with an email.\
     /// Each name is stored individually in the `Vec`. For example "Elton John" results in `Some(["Elton", "John"])`.\
-    /// Be aware that might also get `Some(["Elton John"])` when the `mime` feature is enabled because whitespaces may appear after decoding encoded data.
+    /// Be aware that might also get `Some(["Elton John"])` when the `mime` feature is enabled because whitespaces may appear after decoding encoded data, and adjacent encoded words are merged.\
+    /// Use [Mailbox::display_name] to get the name as a single string.
     pub name: Option<Vec<Cow<'a, str>>>,
+    /// The whitespaces found between the words of the name, unfolded and without comments.\
+    /// It is used by [Mailbox::display_name] and can be left empty, in which case words are separated with a space.
+    pub name_separators: Vec<Cow<'a, str>>,
     pub address: EmailAddress<'a>,
+    /// The comments found in the mailbox, in order, like `John Smith` in `john@example.com (John Smith)`.
+    pub comments: Vec<Cow<'a, str>>,
+    /// Whether the last comment follows the address, as in `john@example.com (John Smith)`.
+    pub trailing_comment: bool,
 }
 
+/// An address like `john.doe@example.com`.\
+/// Addresses are compared with [EmailAddress::same_address]: the local part is case-sensitive, but the domain is not.
+/// Use [EmailAddress::normalize] for looser comparisons.
 #[derive(Debug, Clone)]
 pub struct EmailAddress<'a> {
     pub local_part: Cow<'a, str>,
-    pub domain: Cow<'a, str>,
+    pub domain: Domain<'a>,
 }
 
-#[derive(Debug, Clone)]
+/// The domain of an address: a host name, or an address literal written between brackets.\
+/// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.1.3)
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Domain<'a> {
+    /// A host name, like `example.com`.
+    Name(Cow<'a, str>),
+    /// An IPv4 address literal, like `[192.168.0.1]`.
+    Ipv4(Ipv4Addr),
+    /// An IPv6 address literal, like `[IPv6:::1]`.
+    Ipv6(Ipv6Addr),
+    /// Any other address literal, like `[tag:content]`, without its brackets.\
+    /// Literals that look like IP addresses but are invalid also end up here.
+    Literal(Cow<'a, str>),
+}
+
+/// Checks that a parser consumed all the input, surrounding whitespaces excepted.
+fn complete<'a, T>(
+    input: &'a str,
+    parser: fn(&'a [u8]) -> crate::error::Res<'a, T>,
+) -> Result<T, Error> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(Error::Unknown(
+            "Expected an address, but the input is empty",
+        ));
+    }
+    let (rest, value) = parser(input.as_bytes())?;
+    if !rest.is_empty() {
+        return Err(Error::Unknown(
+            "Unexpected characters after the address, is a comma missing?",
+        ));
+    }
+    Ok(value)
+}
+
+impl<'a> Mailbox<'a> {
+    /// Parses a single mailbox, such as `John Doe <john@example.com>` or `john@example.com`.\
+    /// The input does not need to end with a CRLF sequence.\
+    /// [Learn more](https://tools.ietf.org/html/rfc5322#section-3.4)
+    pub fn parse(input: &'a str) -> Result<Mailbox<'a>, Error> {
+        complete(input, crate::parsing::address::mailbox::<UTF8_HEADERS>)
+    }
+
+    /// Returns the name associated with the address as a single string, like `John Doe` or `Doe, John`.\
+    /// Words are separated by the whitespaces of the original phrase, quoted strings keep their content and adjacent encoded words are not separated.
+    pub fn display_name(&self) -> Option<Cow<'a, str>> {
+        self.name
+            .as_deref()
+            .map(|name| join_phrase(name, &self.name_separators))
+    }
+
+    /// Returns the display name or, when there is none, the comment following the address.\
+    /// Old mailers write the name in a comment, as in `john@example.com (John Smith)`.
+    pub fn display_name_or_comment(&self) -> Option<Cow<'a, str>> {
+        self.display_name().or_else(|| {
+            self.comments
+                .last()
+                .filter(|_| self.trailing_comment)
+                .map(|comment| Cow::<str>::Owned(comment.trim().to_string()))
+                .filter(|comment| !comment.is_empty())
+        })
+    }
+}
+
+impl<'a> EmailAddress<'a> {
+    /// Parses a bare address, such as `john@example.com`, without display name nor angle brackets.\
+    /// The input does not need to end with a CRLF sequence.\
+    /// [Learn more](https://tools.ietf.org/html/rfc5322#section-3.4.1)
+    pub fn parse(input: &'a str) -> Result<EmailAddress<'a>, Error> {
+        complete(input, crate::parsing::address::addr_spec::<UTF8_HEADERS>)
+    }
+
+    /// Returns the domain in its ASCII form, with Unicode labels encoded in punycode (`bücher.de` becomes `xn--bcher-kva.de`).\
+    /// The domain is lowercased. Address literals are returned with their brackets.\
+    /// [Learn more](https://www.unicode.org/reports/tr46/#ToASCII)
+    pub fn domain_to_ascii(&self) -> Result<String, Error> {
+        match &self.domain {
+            Domain::Name(name) => crate::parsing::idna::to_ascii(name),
+            literal => Ok(literal.to_string()),
+        }
+    }
+
+    /// Returns the domain in its Unicode form, with punycode labels decoded (`xn--bcher-kva.de` becomes `bücher.de`).\
+    /// The domain is lowercased. Address literals are returned with their brackets.\
+    /// [Learn more](https://www.unicode.org/reports/tr46/#ToUnicode)
+    pub fn domain_to_unicode(&self) -> Result<String, Error> {
+        match &self.domain {
+            Domain::Name(name) => crate::parsing::idna::to_unicode(name),
+            literal => Ok(literal.to_string()),
+        }
+    }
+
+    /// Checks that the address can be used to send mail with SMTP, which is stricter than the syntax accepted when parsing.\
+    /// Length limits, domain label rules and address literals are checked.
+    /// The error tells which rule is broken.\
+    /// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.5.3.1)
+    pub fn validate(&self) -> Result<(), AddressError> {
+        if self.local_part.is_empty() {
+            return Err(AddressError::EmptyLocalPart);
+        }
+        if self.local_part.chars().any(char::is_control) {
+            return Err(AddressError::InvalidLocalPartCharacter);
+        }
+        let local_part_len = self.quoted_local_part().len();
+        if local_part_len > MAX_LOCAL_PART_LEN {
+            return Err(AddressError::LocalPartTooLong);
+        }
+
+        self.domain.validate()?;
+        let domain_len = self
+            .domain_to_ascii()
+            .map(|domain| domain.len())
+            .unwrap_or_else(|_| self.domain.to_string().len());
+
+        if local_part_len + 1 + domain_len > MAX_ADDRESS_LEN {
+            return Err(AddressError::AddressTooLong);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the domain in the form used to compare addresses: lowercased, and encoded in punycode if possible.\
+    /// Unicode normalization is not applied, so that the result does not depend on the `idna` feature.
+    /// Lowercase ASCII host names are borrowed.
+    fn domain_key(&self) -> Cow<'_, str> {
+        match &self.domain {
+            Domain::Name(name)
+                if name
+                    .bytes()
+                    .all(|c| c.is_ascii() && !c.is_ascii_uppercase()) =>
+            {
+                Cow::Borrowed(name)
+            }
+            Domain::Name(name) => Cow::Owned(
+                crate::parsing::idna::punycode_to_ascii(name)
+                    .unwrap_or_else(|_| name.to_lowercase()),
+            ),
+            literal => Cow::Owned(literal.to_string().to_lowercase()),
+        }
+    }
+
+    /// Returns the local part as it is written before the `@`, quoted if needed.
+    fn quoted_local_part(&self) -> Cow<'_, str> {
+        if EmailAddress::is_dot_atom(&self.local_part) {
+            return Cow::Borrowed(&self.local_part);
+        }
+
+        let mut quoted = String::with_capacity(self.local_part.len() + 2);
+        quoted.push('"');
+        for c in self.local_part.chars() {
+            if c == '"' || c == '\\' {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        Cow::Owned(quoted)
+    }
+
+    /// Returns `true` if both domains are the same, ignoring case and whether they are encoded in punycode.\
+    /// Unicode labels are compared by their punycode encoding, without the normalization of the `idna` feature, so that the result does not depend on enabled features.
+    pub fn same_domain(&self, other: &EmailAddress) -> bool {
+        self.domain_key() == other.domain_key()
+    }
+
+    /// Returns `true` if both addresses are the same.\
+    /// The local parts must be equal, but domains are compared with [EmailAddress::same_domain].
+    pub fn same_address(&self, other: &EmailAddress) -> bool {
+        self.local_part == other.local_part && self.same_domain(other)
+    }
+
+    /// Returns the subaddress of the local part, which is the tag after the first `+` (`tag` in `john+tag@example.com`).\
+    /// [Learn more](https://tools.ietf.org/html/rfc5233)
+    pub fn subaddress(&self) -> Option<&str> {
+        self.local_part.split_once('+').map(|(_, tag)| tag)
+    }
+
+    /// Returns a normalized copy of this address, so that different spellings of the same mailbox become equal.\
+    /// The domain is always converted to its lowercase ASCII form when possible, as in [EmailAddress::same_domain].
+    pub fn normalize(&self, normalization: &Normalization) -> EmailAddress<'static> {
+        let mut domain = self.domain_key().into_owned();
+        let is_name = matches!(self.domain, Domain::Name(_));
+        let mut local_part = self.local_part.to_string();
+
+        let is_gmail =
+            normalization.gmail && is_name && (domain == "gmail.com" || domain == "googlemail.com");
+        if normalization.remove_subaddress || is_gmail {
+            if let Some((base, _tag)) = local_part.split_once('+') {
+                local_part = base.to_string();
+            }
+        }
+        if normalization.fold_local_part || is_gmail {
+            local_part = local_part.to_lowercase();
+        }
+        if is_gmail {
+            local_part.retain(|c| c != '.');
+            domain = String::from("gmail.com");
+        }
+
+        EmailAddress {
+            local_part: Cow::Owned(local_part),
+            domain: match &self.domain {
+                Domain::Name(_) => Domain::Name(Cow::Owned(domain)),
+                Domain::Ipv4(address) => Domain::Ipv4(*address),
+                Domain::Ipv6(address) => Domain::Ipv6(*address),
+                Domain::Literal(literal) => Domain::Literal(Cow::Owned(literal.to_string())),
+            },
+        }
+    }
+
+    /// Returns `true` if the text is a dot-atom, so that a local part can be written without quotes.\
+    /// [Learn more](https://tools.ietf.org/html/rfc5322#section-3.4.1)
+    fn is_dot_atom(text: &str) -> bool {
+        !text.is_empty()
+            && text
+                .split('.')
+                .all(|atom| !atom.is_empty() && atom.bytes().all(is_atext::<UTF8_HEADERS>))
+    }
+}
+
+impl<'a> PartialEq for EmailAddress<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_address(other)
+    }
+}
+
+impl<'a> Eq for EmailAddress<'a> {}
+
+impl<'a> Hash for EmailAddress<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.local_part.hash(state);
+        self.domain_key().hash(state);
+    }
+}
+
+/// Formats the address as in a header, quoting the local part if needed.
+impl<'a> std::fmt::Display for EmailAddress<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.quoted_local_part(), self.domain)
+    }
+}
+
+impl<'a> Domain<'a> {
+    /// Returns the host name, or `None` for address literals.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Domain::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the domain is an address literal, like `[192.168.0.1]`.
+    pub fn is_literal(&self) -> bool {
+        !matches!(self, Domain::Name(_))
+    }
+
+    /// Checks that the domain is a valid host name or a valid address literal.\
+    /// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.1.3)
+    pub fn validate(&self) -> Result<(), AddressError> {
+        use crate::parsing::address::general_literal;
+
+        match self {
+            Domain::Name(name) => crate::parsing::idna::validate(name),
+            Domain::Ipv4(_) | Domain::Ipv6(_) => Ok(()),
+            Domain::Literal(literal) => {
+                if literal.starts_with(|c: char| c.is_ascii_digit())
+                    && literal.bytes().all(|c| c.is_ascii_digit() || c == b'.')
+                {
+                    Err(AddressError::InvalidIpv4Literal)
+                } else if literal.len() >= 5
+                    && literal.is_char_boundary(5)
+                    && literal[..5].eq_ignore_ascii_case("IPv6:")
+                {
+                    Err(AddressError::InvalidIpv6Literal)
+                } else {
+                    general_literal(literal)
+                        .map(|_| ())
+                        .ok_or(AddressError::InvalidAddressLiteral)
+                }
+            }
+        }
+    }
+}
+
+/// Formats the domain as after the `@` of an address, address literals being written between brackets.
+impl<'a> std::fmt::Display for Domain<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Domain::Name(name) => f.write_str(name),
+            Domain::Ipv4(address) => write!(f, "[{}]", address),
+            Domain::Ipv6(address) => write!(f, "[IPv6:{}]", address),
+            Domain::Literal(literal) => write!(f, "[{}]", literal),
+        }
+    }
+}
+
+/// Compares the host name with a string. Address literals are never equal to a string.
+impl<'a> PartialEq<str> for Domain<'a> {
+    fn eq(&self, other: &str) -> bool {
+        self.name() == Some(other)
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for Domain<'a> {
+    fn eq(&self, other: &&'b str) -> bool {
+        self.name() == Some(*other)
+    }
+}
+
+/// The maximum length of a local part, in octets.\
+/// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.5.3.1.1)
+const MAX_LOCAL_PART_LEN: usize = 64;
+
+/// The maximum length of an address, which is the maximum length of a path without its angle brackets.\
+/// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.5.3.1.3)
+const MAX_ADDRESS_LEN: usize = 254;
+
+/// The rule broken by an address, as reported by [EmailAddress::validate].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AddressError {
+    /// The local part is empty.
+    EmptyLocalPart,
+    /// The local part contains control characters.
+    InvalidLocalPartCharacter,
+    /// The local part is longer than 64 octets.
+    LocalPartTooLong,
+    /// The address is longer than 254 octets.
+    AddressTooLong,
+    /// The domain is empty or contains an empty label, as in `example..com`.
+    EmptyDomainLabel,
+    /// The domain is longer than 253 octets once converted to ASCII.
+    DomainTooLong,
+    /// A label of the domain is longer than 63 octets once converted to ASCII.
+    DomainLabelTooLong,
+    /// A label of the domain contains characters other than letters, digits and hyphens.
+    InvalidDomainCharacter,
+    /// A label of the domain starts or ends with a hyphen, or has hyphens in the third and fourth positions without being punycode.
+    InvalidDomainHyphen,
+    /// The domain cannot be converted to ASCII, or contains invalid punycode.
+    InvalidIdn,
+    /// The domain is not a valid IPv4 address literal.
+    InvalidIpv4Literal,
+    /// The domain is not a valid IPv6 address literal.
+    InvalidIpv6Literal,
+    /// The domain is not a valid general address literal.
+    InvalidAddressLiteral,
+}
+
+impl AddressError {
+    fn message(&self) -> &'static str {
+        match self {
+            AddressError::EmptyLocalPart => "The local part of the address is empty",
+            AddressError::InvalidLocalPartCharacter => {
+                "The local part of the address contains control characters"
+            }
+            AddressError::LocalPartTooLong => {
+                "The local part of the address is longer than 64 octets"
+            }
+            AddressError::AddressTooLong => "The address is longer than 254 octets",
+            AddressError::EmptyDomainLabel => "Domain labels cannot be empty",
+            AddressError::DomainTooLong => "Domain is longer than 253 octets",
+            AddressError::DomainLabelTooLong => "Domain label is longer than 63 octets",
+            AddressError::InvalidDomainCharacter => {
+                "Domain labels can only contain letters, digits and hyphens"
+            }
+            AddressError::InvalidDomainHyphen => {
+                "Domain labels cannot start or end with a hyphen, nor contain hyphens in the third and fourth positions"
+            }
+            AddressError::InvalidIdn => "The domain is not a valid internationalized domain name",
+            AddressError::InvalidIpv4Literal => "Invalid IPv4 address literal",
+            AddressError::InvalidIpv6Literal => "Invalid IPv6 address literal",
+            AddressError::InvalidAddressLiteral => "Invalid address literal",
+        }
+    }
+}
+
+impl std::fmt::Display for AddressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+/// Options of [EmailAddress::normalize].\
+/// By default, only the domain is normalized.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Normalization {
+    /// Lowercase the local part.\
+    /// Most providers ignore its case, although RFC 5321 states that it is case-sensitive.
+    pub fold_local_part: bool,
+    /// Remove the [subaddress](EmailAddress::subaddress) from the local part (`john+tag@example.com` becomes `john@example.com`).
+    pub remove_subaddress: bool,
+    /// Apply the rules of Gmail to `gmail.com` and `googlemail.com` addresses: the local part is lowercased, dots and subaddresses are removed, and the domain becomes `gmail.com`.
+    pub gmail: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Address<'a> {
     Mailbox(Mailbox<'a>),
-    Group((Vec<Cow<'a, str>>, Vec<Mailbox<'a>>)),
+    Group(Group<'a>),
+}
+
+/// A named list of mailboxes, like `Team: john@example.com, jane@example.com;`.\
+/// The list can be empty, as in `undisclosed-recipients:;` which is used to hide the recipients of a message.\
+/// [Learn more](https://tools.ietf.org/html/rfc5322#section-3.4)
+#[derive(Debug, PartialEq, Clone)]
+pub struct Group<'a> {
+    /// The name of the group, stored word by word like [Mailbox::name].\
+    /// Use [Group::display_name] to get it as a single string.
+    pub name: Vec<Cow<'a, str>>,
+    /// The whitespaces found between the words of the name, like [Mailbox::name_separators].
+    pub name_separators: Vec<Cow<'a, str>>,
+    pub mailboxes: Vec<Mailbox<'a>>,
+}
+
+impl<'a> Group<'a> {
+    /// Returns the name of the group as a single string, like `undisclosed-recipients`.
+    pub fn display_name(&self) -> Cow<'a, str> {
+        join_phrase(&self.name, &self.name_separators)
+    }
+
+    /// Returns `true` if the group has no member, as in `undisclosed-recipients:;`.
+    pub fn is_empty(&self) -> bool {
+        self.mailboxes.is_empty()
+    }
+}
+
+impl<'a> Address<'a> {
+    /// Parses a comma-separated list of mailboxes and groups, such as a list of recipients typed by a user.\
+    /// For example: `"Doe, John" <john@example.com>, team: a@example.com, b@example.com;`.\
+    /// The input does not need to end with a CRLF sequence.\
+    /// [Learn more](https://tools.ietf.org/html/rfc5322#section-3.4)
+    pub fn parse_list(input: &'a str) -> Result<Vec<Address<'a>>, Error> {
+        complete(input, crate::parsing::address::address_list::<UTF8_HEADERS>)
+    }
+
+    /// Iterates over all the mailboxes of a list, including the members of groups.\
+    /// Each mailbox comes with the group it belongs to, if any. Empty groups yield no mailbox.
+    pub fn mailboxes<'b>(addresses: &'b [Address<'a>]) -> Mailboxes<'b, 'a> {
+        Mailboxes::new(addresses.iter())
+    }
+
+    /// Returns the display name of a mailbox, or the name of a group, as a single string.
+    pub fn display_name(&self) -> Option<Cow<'a, str>> {
+        match self {
+            Address::Mailbox(mailbox) => mailbox.display_name(),
+            Address::Group(group) => Some(group.display_name()),
+        }
+    }
+}
+
+/// An iterator over the mailboxes of address lists, returned by [Address::mailboxes].\
+/// Items are `(mailbox, group)` tuples, where `group` is the group containing the mailbox.
+pub struct Mailboxes<'b, 'a, I = std::slice::Iter<'b, Address<'a>>> {
+    addresses: I,
+    group: Option<(&'b Group<'a>, std::slice::Iter<'b, Mailbox<'a>>)>,
+}
+
+/// The iterator over the to, cc and bcc fields returned by [Email::recipients](crate::prelude::Email::recipients).
+pub type Recipients<'b, 'a> = Mailboxes<
+    'b,
+    'a,
+    std::iter::Chain<
+        std::iter::Chain<std::slice::Iter<'b, Address<'a>>, std::slice::Iter<'b, Address<'a>>>,
+        std::slice::Iter<'b, Address<'a>>,
+    >,
+>;
+
+impl<'b, 'a, I: Iterator<Item = &'b Address<'a>>> Mailboxes<'b, 'a, I> {
+    pub(crate) fn new(addresses: I) -> Self {
+        Mailboxes {
+            addresses,
+            group: None,
+        }
+    }
+}
+
+impl<'b, 'a, I: Iterator<Item = &'b Address<'a>>> Iterator for Mailboxes<'b, 'a, I> {
+    type Item = (&'b Mailbox<'a>, Option<&'b Group<'a>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((group, members)) = &mut self.group {
+                if let Some(mailbox) = members.next() {
+                    return Some((mailbox, Some(*group)));
+                }
+                self.group = None;
+            }
+
+            match self.addresses.next()? {
+                Address::Mailbox(mailbox) => return Some((mailbox, None)),
+                Address::Group(group) => self.group = Some((group, group.mailboxes.iter())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_conversion() {
+        let unicode = EmailAddress {
+            local_part: Cow::Borrowed("user"),
+            domain: Domain::Name(Cow::Borrowed("Bücher.de")),
+        };
+        let ascii = EmailAddress {
+            local_part: Cow::Borrowed("user"),
+            domain: Domain::Name(Cow::Borrowed("xn--bcher-kva.DE")),
+        };
+        assert_eq!(unicode.domain_to_ascii().unwrap(), "xn--bcher-kva.de");
+        assert_eq!(ascii.domain_to_unicode().unwrap(), "bücher.de");
+        assert!(unicode.validate().is_ok());
+        assert!(unicode.same_address(&ascii));
+
+        let other = EmailAddress {
+            local_part: Cow::Borrowed("User"),
+            domain: Domain::Name(Cow::Borrowed("bücher.de")),
+        };
+        assert!(other.same_domain(&ascii));
+        assert!(!other.same_address(&ascii));
+
+        // Comparisons do not normalize Unicode, with or without the `idna` feature.
+        let decomposed = EmailAddress {
+            local_part: Cow::Borrowed("user"),
+            domain: Domain::Name(Cow::Borrowed("bu\u{308}cher.de")),
+        };
+        assert!(!decomposed.same_domain(&ascii));
+    }
+
+    fn address<'a>(local_part: &'a str, domain: &'a str) -> EmailAddress<'a> {
+        let domain = match domain.starts_with('[') {
+            true => {
+                crate::parsing::address::address_domain::<false>(domain.as_bytes())
+                    .unwrap()
+                    .1
+            }
+            false => Domain::Name(Cow::Borrowed(domain)),
+        };
+        EmailAddress {
+            local_part: Cow::Borrowed(local_part),
+            domain,
+        }
+    }
+
+    #[test]
+    fn test_comparison() {
+        use std::collections::HashSet;
+
+        assert_eq!(
+            address("john", "Example.COM"),
+            address("john", "example.com")
+        );
+        assert_ne!(
+            address("John", "example.com"),
+            address("john", "example.com")
+        );
+
+        let contacts: HashSet<EmailAddress> = [
+            address("user", "bücher.de"),
+            address("user", "xn--bcher-kva.de"),
+            address("user", "BÜCHER.DE"),
+            address("other", "bücher.de"),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(contacts.len(), 2);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let gmail = Normalization {
+            gmail: true,
+            ..Normalization::default()
+        };
+        assert_eq!(
+            address("John.Doe+news", "GoogleMail.com").normalize(&gmail),
+            address("johndoe", "gmail.com")
+        );
+        assert_eq!(
+            address("John.Doe+news", "example.com").normalize(&gmail),
+            address("John.Doe+news", "example.com")
+        );
+
+        let loose = Normalization {
+            fold_local_part: true,
+            remove_subaddress: true,
+            gmail: false,
+        };
+        let normalized = address("John.Doe+news", "Bücher.de").normalize(&loose);
+        assert_eq!(normalized.local_part, "john.doe");
+        assert_eq!(normalized.domain, "xn--bcher-kva.de");
+        assert_eq!(
+            address("john+news+2", "example.com").subaddress(),
+            Some("news+2")
+        );
+        assert_eq!(address("john", "example.com").subaddress(), None);
+    }
+
+    #[test]
+    fn test_groups() {
+        let addresses = Address::parse_list(
+            "a@x.org, undisclosed-recipients:;, Team: b@x.org, c@x.org;, Empty: ;",
+        )
+        .unwrap();
+        assert_eq!(addresses.len(), 4);
+        match &addresses[1] {
+            Address::Group(group) => {
+                assert!(group.is_empty());
+                assert_eq!(group.display_name(), "undisclosed-recipients");
+            }
+            _ => panic!("Expected a group"),
+        }
+
+        let mailboxes: Vec<_> = Address::mailboxes(&addresses)
+            .map(|(mailbox, group)| {
+                (
+                    mailbox.address.local_part.as_ref(),
+                    group.map(|group| group.display_name()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            mailboxes,
+            [
+                ("a", None),
+                ("b", Some(Cow::Borrowed("Team"))),
+                ("c", Some(Cow::Borrowed("Team"))),
+            ]
+        );
+        assert_eq!(Address::mailboxes(&[]).count(), 0);
+    }
+
+    #[test]
+    fn test_parse() {
+        let addresses =
+            Address::parse_list("\"Doe, John\" <john@x.org>, team: a@b.c, d@e.f; ").unwrap();
+        assert_eq!(addresses.len(), 2);
+        match &addresses[0] {
+            Address::Mailbox(mailbox) => {
+                assert_eq!(mailbox.name.as_ref().unwrap(), &vec!["Doe, John"]);
+                assert_eq!(mailbox.address, address("john", "x.org"));
+            }
+            _ => panic!("Expected a mailbox"),
+        }
+        match &addresses[1] {
+            Address::Group(group) => {
+                assert_eq!(group.name, vec!["team"]);
+                assert_eq!(group.mailboxes.len(), 2);
+            }
+            _ => panic!("Expected a group"),
+        }
+
+        assert_eq!(addresses[0].display_name().unwrap(), "Doe, John");
+        assert_eq!(addresses[1].display_name().unwrap(), "team");
+
+        let mailbox = Mailbox::parse(" John Doe <john@example.com>").unwrap();
+        assert_eq!(mailbox.display_name().unwrap(), "John Doe");
+        assert_eq!(mailbox.name.unwrap(), vec!["John", "Doe"]);
+        assert!(Mailbox::parse("john@example.com")
+            .unwrap()
+            .display_name()
+            .is_none());
+
+        let mailbox = Mailbox::parse("john@example.com (John Smith)").unwrap();
+        assert!(mailbox.display_name().is_none());
+        assert_eq!(mailbox.comments, vec!["John Smith"]);
+        assert_eq!(mailbox.display_name_or_comment().unwrap(), "John Smith");
+        let mailbox = Mailbox::parse("John (the admin) <john(work)@example.com> (Johnny)").unwrap();
+        assert_eq!(mailbox.comments, vec!["the admin", "work", "Johnny"]);
+        assert_eq!(mailbox.display_name_or_comment().unwrap(), "John");
+        let mailbox = Mailbox::parse("john(work)@example.com").unwrap();
+        assert_eq!(mailbox.comments, vec!["work"]);
+        assert!(!mailbox.trailing_comment);
+        assert!(mailbox.display_name_or_comment().is_none());
+        let mailbox = Mailbox::parse("(work) john@example.com (John Smith)\r\n").unwrap();
+        assert!(mailbox.trailing_comment);
+        assert_eq!(mailbox.display_name_or_comment().unwrap(), "John Smith");
+        assert_eq!(
+            EmailAddress::parse("john@example.com\r\n").unwrap(),
+            address("john", "example.com")
+        );
+
+        assert!(EmailAddress::parse("").is_err());
+        assert!(EmailAddress::parse("John <john@example.com>").is_err());
+        assert!(Mailbox::parse("john@example.com, jane@example.com").is_err());
+        assert!(Address::parse_list("john@example.com jane@example.com").is_err());
+        assert!(Address::parse_list("john@").is_err());
+    }
+
+    #[cfg(feature = "mime")]
+    #[test]
+    fn test_encoded_display_name() {
+        let mailbox = Mailbox::parse(
+            "=?UTF-8?Q?Andr?= =?UTF-8?Q?=C3=A9?= \"Pirard, Jr\" <andre@example.com>",
+        )
+        .unwrap();
+        assert_eq!(mailbox.display_name().unwrap(), "André Pirard, Jr");
+
+        let mailbox = Mailbox::parse("=?utf-8?q?a_?= b <a@example.com>").unwrap();
+        assert_eq!(mailbox.name.as_ref().unwrap(), &vec!["a ", "b"]);
+        assert_eq!(mailbox.display_name().unwrap(), "a  b");
+
+        let mailbox = Mailbox::parse("=?utf-8?q?a?=  =?utf-8?q?b?=\tc <a@example.com>").unwrap();
+        assert_eq!(mailbox.display_name().unwrap(), "ab\tc");
+    }
+
+    #[test]
+    fn test_display_name_separators() {
+        let mailbox = Mailbox::parse("John   Smith <john@example.com>").unwrap();
+        assert_eq!(mailbox.name.as_ref().unwrap(), &vec!["John", "Smith"]);
+        assert_eq!(mailbox.display_name().unwrap(), "John   Smith");
+
+        let mailbox = Mailbox::parse("John\r\n Smith (Jr)\t\"Doe\" <john@example.com>").unwrap();
+        assert_eq!(mailbox.display_name().unwrap(), "John Smith \tDoe");
+
+        let mut mailbox = Mailbox::parse("John  Smith <john@example.com>").unwrap();
+        mailbox.name_separators.clear();
+        assert_eq!(mailbox.display_name().unwrap(), "John Smith");
+
+        let addresses = Address::parse_list("The   team: john@example.com;").unwrap();
+        assert_eq!(addresses[0].display_name().unwrap(), "The   team");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(address("john.doe", "example.com").validate(), Ok(()));
+        assert_eq!(address("josé", "bücher.de").validate(), Ok(()));
+        assert_eq!(address("root", "[192.0.2.1]").validate(), Ok(()));
+        assert_eq!(address("root", "[IPv6:2001:db8::1]").validate(), Ok(()));
+        assert_eq!(address("root", "[x-tag:content]").validate(), Ok(()));
+
+        let long_local_part = "a".repeat(65);
+        let long_label = "a".repeat(64);
+        let long_domain = format!("{}.com", ["a"; 127].join("."));
+        let max_local_part = "a".repeat(64);
+        let max_domain = format!("{}.com", ["a"; 95].join("."));
+        let cases = [
+            (address("", "example.com"), AddressError::EmptyLocalPart),
+            (
+                address("a\tb", "example.com"),
+                AddressError::InvalidLocalPartCharacter,
+            ),
+            (
+                address(&long_local_part, "example.com"),
+                AddressError::LocalPartTooLong,
+            ),
+            (address("a", &long_domain), AddressError::DomainTooLong),
+            (address("a", "example..com"), AddressError::EmptyDomainLabel),
+            (address("a", &long_label), AddressError::DomainLabelTooLong),
+            (
+                address("a", "under_score.com"),
+                AddressError::InvalidDomainCharacter,
+            ),
+            (
+                address("a", "-example.com"),
+                AddressError::InvalidDomainHyphen,
+            ),
+            (address("a", "xn--example-.com"), AddressError::InvalidIdn),
+            (
+                address("a", "[192.0.2.300]"),
+                AddressError::InvalidIpv4Literal,
+            ),
+            (
+                address("a", "[IPv6:1::2::3]"),
+                AddressError::InvalidIpv6Literal,
+            ),
+            (
+                address("a", "[tag-:content]"),
+                AddressError::InvalidAddressLiteral,
+            ),
+            (
+                address(&max_local_part, &max_domain),
+                AddressError::AddressTooLong,
+            ),
+        ];
+        for (address, error) in cases.iter() {
+            assert_eq!(address.validate(), Err(*error), "{}", address);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            address("john.doe", "example.com").to_string(),
+            "john.doe@example.com"
+        );
+        assert_eq!(
+            address("john doe", "example.com").to_string(),
+            "\"john doe\"@example.com"
+        );
+        assert_eq!(
+            address(".a\"b\\c", "example.com").to_string(),
+            "\".a\\\"b\\\\c\"@example.com"
+        );
+        assert_eq!(
+            address("a..b", "example.com").to_string(),
+            "\"a..b\"@example.com"
+        );
+        assert_eq!(address("root", "[IPv6:::1]").to_string(), "root@[IPv6:::1]");
+        assert_eq!(
+            address("root", "[192.168.0.1]").to_string(),
+            "root@[192.168.0.1]"
+        );
+        assert_eq!(
+            address("root", "[tag:content]").to_string(),
+            "root@[tag:content]"
+        );
+
+        let address = address("user", "bücher.de");
+        assert_eq!(address.to_string(), "user@bücher.de");
+        #[cfg(feature = "utf8-headers")]
+        assert_eq!(
+            EmailAddress::parse(&address.to_string()).unwrap().domain,
+            Domain::Name(Cow::Borrowed("bücher.de"))
+        );
+    }
 }
@@ -25,7 +25,7 @@ use std::borrow::Cow;
 /// assert_eq!(email.sender.address.local_part, "mubelotix");
 /// assert_eq!(email.sender.address.domain, "mubelotix.dev");
 /// ```
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Email<'a> {
     /// The ASCII text of the body.
     #[cfg(not(feature = "mime"))]
@@ -51,6 +51,10 @@ pub struct Email<'a> {
     /// For instance, this might be the time that a user pushes the "send" or "submit" button in an application program.
     pub date: DateTime,
 
+    #[cfg(feature = "date")]
+    /// Whether the [date](#structfield.date) follows RFC 5322 or had to be parsed with heuristics.
+    pub date_confidence: DateConfidence,
+
     #[cfg(feature = "to")]
     pub to: Option<Vec<Address<'a>>>,
 
@@ -91,12 +95,30 @@ pub struct Email<'a> {
     /// The list of unrecognized fields.\
     /// Each field is stored as a `(name, value)` tuple.
     pub unknown_fields: Vec<(&'a str, Cow<'a, str>)>,
+
+    /// The header section of the message, returned by [Email::raw_header].
+    raw_header: &'a [u8],
 }
 
 impl<'a> Email<'a> {
     /// Parse an email.
     pub fn parse(data: &'a [u8]) -> Result<Email<'a>, Error> {
-        let (fields, body) = crate::parse_message(data)?;
+        Email::parse_with::<UTF8_HEADERS>(data)
+    }
+
+    /// Returns the header section of the message, exactly as it appears in the raw data.\
+    /// It includes the CRLF sequence ending the last field but not the empty line separating the header from the body.
+    pub fn raw_header(&self) -> &'a [u8] {
+        self.raw_header
+    }
+
+    /// Like [parse](Email::parse), but `UTF8` tells whether header fields can contain UTF-8.
+    pub(crate) fn parse_with<const UTF8: bool>(data: &'a [u8]) -> Result<Email<'a>, Error> {
+        let (fields, body) = crate::parsing::message::parse_message_with::<UTF8>(data)?;
+        let raw_header = match &body {
+            Some(body) => &data[..data.len() - body.len() - 2],
+            None => data,
+        };
 
         #[cfg(feature = "from")]
         let mut from = None;
@@ -168,9 +190,9 @@ impl<'a> Email<'a> {
                     }
                 }
                 #[cfg(feature = "date")]
-                Field::Date(data) => {
+                Field::Date(data, confidence) => {
                     if date.is_none() {
-                        date = Some(data)
+                        date = Some((data, confidence))
                     } else {
                         return Err(Error::DuplicateHeader("Date"));
                     }
@@ -306,7 +328,7 @@ impl<'a> Email<'a> {
         #[cfg(feature = "from")]
         let from = from.ok_or(Error::MissingHeader("From"))?;
         #[cfg(feature = "date")]
-        let date = date.ok_or(Error::MissingHeader("Date"))?;
+        let (date, date_confidence) = date.ok_or(Error::MissingHeader("Date"))?;
 
         #[cfg(feature = "sender")]
         let sender = match sender {
@@ -350,6 +372,8 @@ impl<'a> Email<'a> {
             subject,
             #[cfg(feature = "date")]
             date,
+            #[cfg(feature = "date")]
+            date_confidence,
             #[cfg(feature = "to")]
             to,
             #[cfg(feature = "cc")]
@@ -383,10 +407,75 @@ impl<'a> Email<'a> {
                 additional_headers: Vec::new(),
             },
             unknown_fields,
+            raw_header,
         })
     }
 }
 
+#[cfg(any(feature = "to", feature = "cc", feature = "bcc"))]
+impl<'a> Email<'a> {
+    /// Iterate over all the recipients of this email, from the [to](#structfield.to), [cc](#structfield.cc) and [bcc](#structfield.bcc) fields.\
+    /// Members of groups are included along with their group. Empty groups, like `undisclosed-recipients:;`, yield no recipient.
+    pub fn recipients(&self) -> Recipients<'_, 'a> {
+        #[cfg(feature = "to")]
+        let to = self.to.as_deref().unwrap_or_default();
+        #[cfg(not(feature = "to"))]
+        let to: &[Address] = &[];
+        #[cfg(feature = "cc")]
+        let cc = self.cc.as_deref().unwrap_or_default();
+        #[cfg(not(feature = "cc"))]
+        let cc: &[Address] = &[];
+        #[cfg(feature = "bcc")]
+        let bcc = self.bcc.as_deref().unwrap_or_default();
+        #[cfg(not(feature = "bcc"))]
+        let bcc: &[Address] = &[];
+
+        Mailboxes::new(to.iter().chain(cc).chain(bcc))
+    }
+}
+
+#[cfg(feature = "mime")]
+impl<'a> Email<'a> {
+    /// Iterate over the [attachments](Attachment) contained anywhere in the MIME tree of this email.\
+    /// Parts that are displayed as the body of the message are not included.
+    pub fn attachments(&self) -> Attachments<'a> {
+        Attachments {
+            stack: vec![self.mime_entity.clone()],
+        }
+    }
+
+    /// Get the decoded plain text body of this email.\
+    /// The MIME tree is walked following the rules of RFC 2046: the last supported alternative is preferred and attachments are ignored.\
+    /// If there is no plain text part, the HTML body is converted to text.
+    /// Flowed text (`format=flowed`) is unwrapped.
+    pub fn text_body(&self) -> Option<Cow<'a, str>> {
+        crate::parsing::mime::body::text_body(self.mime_entity.clone())
+    }
+
+    /// Get the decoded HTML body of this email.\
+    /// The MIME tree is walked following the rules of RFC 2046: the last supported alternative is preferred and attachments are ignored.
+    pub fn html_body(&self) -> Option<Cow<'a, str>> {
+        crate::parsing::mime::body::html_body(self.mime_entity.clone())
+    }
+
+    /// Get the [segments](HeaderSegment) of the first header field with the given name (case-insensitive), exposing the charset and the language of its encoded words.\
+    /// It is intended for unstructured fields such as the subject.
+    /// Whitespaces preceding the value are ignored.
+    pub fn header_segments(&self, name: &str) -> Option<Vec<HeaderSegment<'a>>> {
+        let (name_len, field) = crate::parsing::fields::header_fields(self.raw_header)
+            .into_iter()
+            .find(|(field_name, _)| field_name.eq_ignore_ascii_case(name.as_bytes()))
+            .map(|(field_name, field)| (field_name.len(), field))?;
+        let mut value = &field[name_len + 1..];
+        value = value.strip_suffix(b"\r\n").unwrap_or(value);
+        while let Some((b' ', rest)) | Some((b'\t', rest)) = value.split_first() {
+            value = rest;
+        }
+
+        Some(HeaderSegment::parse(value))
+    }
+}
+
 impl<'a> std::convert::TryFrom<&'a [u8]> for Email<'a> {
     type Error = crate::error::Error;
 
@@ -412,6 +501,64 @@ mod test {
         }*/
     }
 
+    #[cfg(all(feature = "date", feature = "to", feature = "cc"))]
+    #[test]
+    fn test_recipients() {
+        let email = Email::parse(
+            b"\
+            From: Mubelotix <mubelotix@mubelotix.dev>\r\n\
+            Date: Mon, 5 May 2003 18:58:34 +0000\r\n\
+            To: undisclosed-recipients:;\r\n\
+            Cc: Someone <guy@gmail.com>, Developers: mubelotix@gmail.com;\r\n\
+            Bcc:\r\n\
+            \r\n\
+            Hey!\r\n",
+        )
+        .unwrap();
+        match &email.to.as_ref().unwrap()[0] {
+            Address::Group(group) => assert!(group.is_empty()),
+            _ => panic!("Expected a group"),
+        }
+        let recipients: Vec<_> = email.recipients().collect();
+        assert_eq!(recipients.len(), 2);
+        assert!(recipients[0].1.is_none());
+        assert_eq!(recipients[1].1.unwrap().display_name(), "Developers");
+    }
+
+    #[cfg(feature = "date")]
+    #[test]
+    fn test_lenient_date() {
+        let email = Email::parse(
+            b"\
+            From: Mubelotix <mubelotix@mubelotix.dev>\r\n\
+            Date: Mon, 5 May 2003 18:58:34 +0000\r\n\
+            \r\n\
+            Hey!\r\n",
+        )
+        .unwrap();
+        assert_eq!(email.date_confidence, DateConfidence::Exact);
+
+        let email = Email::parse(
+            b"\
+            From: Mubelotix <mubelotix@mubelotix.dev>\r\n\
+            Date: Mon, 5 May 2003 20:58:34 CEST\r\n\
+            \r\n\
+            Hey!\r\n",
+        )
+        .unwrap();
+        assert_eq!(email.date.timestamp(), 1052161114);
+        assert_eq!(email.date_confidence, DateConfidence::Tolerant);
+
+        assert!(Email::parse(
+            b"\
+            From: Mubelotix <mubelotix@mubelotix.dev>\r\n\
+            Date: someday\r\n\
+            \r\n\
+            Hey!\r\n",
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_field_number() {
         assert!(Email::parse(
@@ -454,4 +601,72 @@ mod test {
         )
         .is_err());
     }
+
+    #[test]
+    fn test_raw_header() {
+        let header =
+            b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\n";
+        let mut data = header.to_vec();
+        data.extend_from_slice(b"\r\nHey!\r\n");
+        assert_eq!(Email::parse(&data).unwrap().raw_header(), &header[..]);
+        assert_eq!(Email::parse(header).unwrap().raw_header(), &header[..]);
+    }
+
+    #[cfg(feature = "mime")]
+    #[test]
+    fn test_header_segments() {
+        let email = Email::parse(
+            b"\
+            From: =?utf-8*fr?Q?Andr=C3=A9?= <andre@example.com>\r\n\
+            Subject: =?iso-8859-1*de?Q?Gr=FC=DFe?=\r\n =?iso-8859-1*de?Q?_aus_Berlin?=\r\n\
+            Date: 5 May 2003 18:58:34 +0000\r\n\
+            \r\n\
+            Hey!\r\n",
+        )
+        .unwrap();
+
+        let subject = email.header_segments("subject").unwrap();
+        assert_eq!(subject.len(), 1);
+        assert_eq!(subject[0].text, "Grüße aus Berlin");
+        assert_eq!(subject[0].charset.as_ref().unwrap(), "iso-8859-1");
+        assert_eq!(subject[0].language.as_ref().unwrap(), "de");
+
+        let from = email.header_segments("FROM").unwrap();
+        assert_eq!(from[0].text, "André");
+        assert_eq!(from[0].language.as_ref().unwrap(), "fr");
+        assert_eq!(from[1].text, " <andre@example.com>");
+
+        assert!(email.header_segments("To").is_none());
+    }
+
+    #[cfg(feature = "utf8-headers")]
+    #[test]
+    fn test_utf8_headers() {
+        // Malformed dates with non-ASCII characters are rejected without panicking
+        assert!(
+            Email::parse(b"From: a@b.c\r\nDate: 2003-05-0\xC3\xA9x 18:00 +0000\r\n\r\nhi\r\n")
+                .is_err()
+        );
+
+        let email = Email::parse(
+            "\
+            From: José <josé@exämple.de>\r\n\
+            Subject:Grüße aus Köln\r\n\
+            Date: 5 May 2003 18:58:34 +0000\r\n\
+            \r\n\
+            Hey!\r\n"
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(email.subject.unwrap(), "Grüße aus Köln");
+        assert_eq!(email.sender.name.unwrap(), vec!["José"]);
+        assert_eq!(email.sender.address.local_part, "josé");
+        assert_eq!(email.sender.address.domain, "exämple.de");
+
+        assert!(Email::parse(
+            b"From: jos\xC3 <jose@example.de>\r\nDate: 5 May 2003 18:58:34 +0000\r\n\r\nHey!\r\n"
+        )
+        .is_err());
+    }
 }
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// A generic MIME Entity.
 #[derive(Debug, PartialEq, Clone)]
@@ -10,8 +10,8 @@ pub struct RawEntity<'a> {
     pub subtype: Cow<'a, str>,
     pub description: Option<Cow<'a, str>>,
     pub id: Option<(Cow<'a, str>, Cow<'a, str>)>,
-    /// Parameters named in lowercase.
-    pub parameters: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    /// The parameters of the Content-Type header.
+    pub parameters: Parameters<'a>,
     #[cfg(feature = "content-disposition")]
     pub disposition: Option<Disposition<'a>>,
     /// The raw value of this entity.
@@ -21,12 +21,40 @@ pub struct RawEntity<'a> {
 }
 
 impl<'a> RawEntity<'a> {
-    /// Use this function to decode [text](Entity::Text) and [multipart](Entity::Multipart) values.\
+    /// Use this function to decode [text](Entity::Text), [multipart](Entity::Multipart), [message](Entity::Message) and [external-body](Entity::ExternalBody) values.\
     /// If this library is not able to provide a higher-level structure, the data will be returned [untouched]([Entity::Unknown]).\
-    /// If this entity is supported but is wrongly formatted, an error will be returned.
+    /// If this entity is supported but is wrongly formatted, an error will be returned.\
+    /// Encapsulated [messages](Entity::Message) are an exception: a message that cannot be parsed is returned [untouched](Entity::Unknown), so that a broken forwarded message does not make its parent unreadable.
     pub fn parse(&'a self) -> Result<Entity<'a>, Error> {
         crate::parsing::mime::entity::entity(self)
     }
+
+    /// Extends the lifetime from `'a` to `'static` by guaranteeing that we have ownership after calling this function.
+    /// It will call `to_owned` on references.
+    pub fn into_owned(self) -> RawEntity<'static> {
+        RawEntity {
+            mime_type: self.mime_type.into_owned(),
+            subtype: Cow::Owned(self.subtype.into_owned()),
+            description: self
+                .description
+                .map(|description| Cow::Owned(description.into_owned())),
+            id: self.id.map(|(left, right)| {
+                (
+                    Cow::Owned(left.into_owned()),
+                    Cow::Owned(right.into_owned()),
+                )
+            }),
+            parameters: self.parameters.into_owned(),
+            #[cfg(feature = "content-disposition")]
+            disposition: self.disposition.map(|disposition| disposition.into_owned()),
+            value: Cow::Owned(self.value.into_owned()),
+            additional_headers: self
+                .additional_headers
+                .into_iter()
+                .map(|(n, v)| (Cow::Owned(n.into_owned()), Cow::Owned(v.into_owned())))
+                .collect(),
+        }
+    }
 }
 
 /// A higher-level reprentation of entities.\
@@ -45,10 +73,81 @@ pub enum Entity<'a> {
         subtype: &'a Cow<'a, str>,
         value: Cow<'a, str>,
     },
+    /// An encapsulated message (`message/rfc822` or `message/global`).\
+    /// It is parsed just like any other message, so forwarded messages can be inspected recursively.\
+    /// Messages that cannot be parsed are returned as [Entity::Unknown] instead.\
+    /// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.1)
+    Message(Box<Email<'a>>),
+    /// A reference to data that is not included in the message (`message/external-body`).\
+    /// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.3)
+    ExternalBody(Box<ExternalBody<'a>>),
     /// All other entities that are not supported by this library.
     Unknown,
 }
 
+impl<'a> Entity<'a> {
+    /// Returns the readable text of a text entity.\
+    /// HTML is converted to plain text with the default [options](HtmlToText).
+    /// Returns `None` for other entities.
+    pub fn to_plain_text(&self) -> Option<Cow<'a, str>> {
+        match self {
+            Entity::Text { subtype, value } if subtype.as_ref() == "html" => {
+                Some(Cow::Owned(HtmlToText::default().convert(value)))
+            }
+            Entity::Text { value, .. } => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The mechanism by which the data referenced by an [ExternalBody] can be accessed.\
+/// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.3.1)
+#[derive(Debug, PartialEq, Clone)]
+pub enum AccessType<'a> {
+    /// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.3.2)
+    Ftp,
+    /// Same as [AccessType::Ftp] but the user should be `anonymous`.\
+    /// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.3.2)
+    AnonFtp,
+    /// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.3.2)
+    Tftp,
+    /// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.3.3)
+    LocalFile,
+    /// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.3.4)
+    MailServer,
+    /// [Learn more](https://tools.ietf.org/html/rfc2017)
+    Url,
+    /// An access type that is not supported by this library (in lowercase).
+    Unknown(Cow<'a, str>),
+}
+
+/// The description of data referenced by a `message/external-body` entity.\
+/// Can be obtained with [RawEntity::parse].\
+/// Parameters are only meaningful for some [access types](AccessType).
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExternalBody<'a> {
+    pub access_type: AccessType<'a>,
+    /// The date after which the data may no longer exist.
+    pub expiration: Option<DateTime>,
+    /// The size in bytes of the data, before any content transfer encoding.
+    pub size: Option<usize>,
+    /// Either `read` or `read-write`.
+    pub permission: Option<Cow<'a, str>>,
+    pub name: Option<Cow<'a, str>>,
+    pub site: Option<Cow<'a, str>>,
+    pub directory: Option<Cow<'a, str>>,
+    pub mode: Option<Cow<'a, str>>,
+    pub server: Option<Cow<'a, str>>,
+    pub subject: Option<Cow<'a, str>>,
+    /// The URL of the data, without the whitespaces that may have been used to fold it.
+    pub url: Option<Cow<'a, str>>,
+    /// The encoding in which the referenced data is stored.
+    pub content_transfer_encoding: ContentTransferEncoding<'a>,
+    /// The phantom headers describing the referenced data.\
+    /// Its value is the body of the external-body entity (commands to send to the server in the case of [AccessType::MailServer]), and not the referenced data.
+    pub entity: RawEntity<'a>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ContentType<'a> {
     Text,
@@ -125,7 +224,8 @@ pub struct Disposition<'a> {
     pub creation_date: Option<DateTime>,
     pub modification_date: Option<DateTime>,
     pub read_date: Option<DateTime>,
-    pub unstructured: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    /// The other parameters.
+    pub unstructured: Parameters<'a>,
 }
 
 impl<'a> Disposition<'a> {
@@ -140,11 +240,7 @@ impl<'a> Disposition<'a> {
             creation_date: self.creation_date,
             modification_date: self.modification_date,
             read_date: self.read_date,
-            unstructured: self
-                .unstructured
-                .into_iter()
-                .map(|(n, v)| (Cow::Owned(n.into_owned()), Cow::Owned(v.into_owned())))
-                .collect(),
+            unstructured: self.unstructured.into_owned(),
         }
     }
 }
@@ -194,3 +290,828 @@ impl<'a> ContentTransferEncoding<'a> {
         }
     }
 }
+
+/// A parameter of a Content-Type or a Content-Disposition header.\
+/// [Learn more](https://tools.ietf.org/html/rfc2045#section-5.1)
+#[derive(Debug, PartialEq, Clone)]
+pub struct Parameter<'a> {
+    /// The name (in lowercase).
+    pub name: Cow<'a, str>,
+    /// The decoded value.\
+    /// Values split in several sections have been reassembled.
+    pub value: Cow<'a, str>,
+    /// The charset in which the value was encoded (in lowercase), if it was encoded.\
+    /// [Learn more](https://tools.ietf.org/html/rfc2231#section-4)
+    pub charset: Option<Cow<'a, str>>,
+    /// The language of the value, if specified.\
+    /// [Learn more](https://tools.ietf.org/html/rfc2231#section-4)
+    pub language: Option<Cow<'a, str>>,
+}
+
+impl<'a> Parameter<'a> {
+    /// Extends the lifetime from `'a` to `'static` by guaranteeing that we have ownership after calling this function.
+    /// It will call `to_owned` on references.
+    pub fn into_owned(self) -> Parameter<'static> {
+        Parameter {
+            name: Cow::Owned(self.name.into_owned()),
+            value: Cow::Owned(self.value.into_owned()),
+            charset: self.charset.map(|charset| Cow::Owned(charset.into_owned())),
+            language: self
+                .language
+                .map(|language| Cow::Owned(language.into_owned())),
+        }
+    }
+}
+
+/// The ordered list of the parameters of a Content-Type or a Content-Disposition header.\
+/// Names are case-insensitive and appear only once, in the order of their first occurrence.
+/// Parameters split in several sections (RFC 2231) are reassembled even if sections are out of order or missing.\
+/// [Learn more](https://tools.ietf.org/html/rfc2231)
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Parameters<'a> {
+    parameters: Vec<Parameter<'a>>,
+}
+
+impl<'a> Parameters<'a> {
+    pub fn new() -> Parameters<'a> {
+        Parameters::default()
+    }
+
+    /// Get the parameter with the given name.
+    pub fn get_parameter(&self, name: &str) -> Option<&Parameter<'a>> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Get the value of the parameter with the given name.
+    pub fn get(&self, name: &str) -> Option<&Cow<'a, str>> {
+        self.get_parameter(name).map(|parameter| &parameter.value)
+    }
+
+    /// Get the language of the parameter with the given name, if specified.
+    pub fn language(&self, name: &str) -> Option<&Cow<'a, str>> {
+        self.get_parameter(name)
+            .and_then(|parameter| parameter.language.as_ref())
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get_parameter(name).is_some()
+    }
+
+    /// Add a parameter.\
+    /// If there is already a parameter with this name, it is replaced but keeps its position.
+    pub fn insert(&mut self, parameter: Parameter<'a>) {
+        match self
+            .parameters
+            .iter_mut()
+            .find(|existing| existing.name.eq_ignore_ascii_case(&parameter.name))
+        {
+            Some(existing) => *existing = parameter,
+            None => self.parameters.push(parameter),
+        }
+    }
+
+    /// Remove the parameter with the given name and return its value.
+    pub fn remove(&mut self, name: &str) -> Option<Cow<'a, str>> {
+        let idx = self
+            .parameters
+            .iter()
+            .position(|parameter| parameter.name.eq_ignore_ascii_case(name))?;
+        Some(self.parameters.remove(idx).value)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Parameter<'a>> {
+        self.parameters.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// Extends the lifetime from `'a` to `'static` by guaranteeing that we have ownership after calling this function.
+    /// It will call `to_owned` on references.
+    pub fn into_owned(self) -> Parameters<'static> {
+        Parameters {
+            parameters: self
+                .parameters
+                .into_iter()
+                .map(|parameter| parameter.into_owned())
+                .collect(),
+        }
+    }
+}
+
+impl<'a> std::iter::FromIterator<Parameter<'a>> for Parameters<'a> {
+    fn from_iter<T: IntoIterator<Item = Parameter<'a>>>(iter: T) -> Self {
+        let mut parameters = Parameters::new();
+        for parameter in iter {
+            parameters.insert(parameter);
+        }
+        parameters
+    }
+}
+
+/// Collects `(name, value)` pairs as parameters without charset nor language.
+impl<'a> std::iter::FromIterator<(Cow<'a, str>, Cow<'a, str>)> for Parameters<'a> {
+    fn from_iter<T: IntoIterator<Item = (Cow<'a, str>, Cow<'a, str>)>>(iter: T) -> Self {
+        iter.into_iter()
+            .map(|(name, value)| Parameter {
+                name,
+                value,
+                charset: None,
+                language: None,
+            })
+            .collect()
+    }
+}
+
+impl<'a> IntoIterator for Parameters<'a> {
+    type Item = Parameter<'a>;
+    type IntoIter = std::vec::IntoIter<Parameter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.parameters.into_iter()
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b Parameters<'a> {
+    type Item = &'b Parameter<'a>;
+    type IntoIter = std::slice::Iter<'b, Parameter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.parameters.iter()
+    }
+}
+
+/// A structure collecting `message/partial` fragments until the original message can be rebuilt.\
+/// Fragments can be added in any order and fragments of different messages can be mixed.\
+/// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.2)
+///
+/// # Example
+///
+/// ```
+/// # use email_parser::prelude::*;
+/// # use email_parser::mime::PartialReassembler;
+/// # let fragments: Vec<Email> = Vec::new();
+/// let mut reassembler = PartialReassembler::new();
+/// for fragment in fragments.iter() {
+///     if let Some(message) = reassembler.add(fragment).unwrap() {
+///         let email = Email::parse(&message).unwrap();
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct PartialReassembler {
+    messages: HashMap<String, PartialMessage>,
+}
+
+#[derive(Debug, Default)]
+struct PartialMessage {
+    total: Option<usize>,
+    enclosing_header: Option<Vec<u8>>,
+    fragments: BTreeMap<usize, Vec<u8>>,
+}
+
+impl PartialReassembler {
+    /// Create a reassembler without any fragment.
+    pub fn new() -> PartialReassembler {
+        PartialReassembler::default()
+    }
+
+    /// Add a fragment.\
+    /// When this fragment was the last missing one, the raw data of the rebuilt message is returned. It can then be parsed with [Email::parse].\
+    /// An error is returned if the email is not a valid `message/partial` fragment.
+    pub fn add(&mut self, email: &Email) -> Result<Option<Vec<u8>>, Error> {
+        let entity = &email.mime_entity;
+        if entity.mime_type != ContentType::Message || entity.subtype != "partial" {
+            return Err(Error::Unknown("Not a message/partial entity"));
+        }
+
+        let id = entity
+            .parameters
+            .get("id")
+            .ok_or(Error::Unknown("Missing id parameter"))?;
+        let number = entity
+            .parameters
+            .get("number")
+            .ok_or(Error::Unknown("Missing number parameter"))?
+            .parse::<usize>()
+            .map_err(|_| Error::Unknown("Invalid number parameter"))?;
+        let total = match entity.parameters.get("total") {
+            Some(total) => Some(
+                total
+                    .parse::<usize>()
+                    .map_err(|_| Error::Unknown("Invalid total parameter"))?,
+            ),
+            None => None,
+        };
+        if number == 0 {
+            return Err(Error::Unknown("Fragment number out of range"));
+        }
+
+        // Nothing is stored before the fragment is known to be valid
+        let known = self.messages.get(id.as_ref());
+        let known_total = known.and_then(|message| message.total);
+        if known.is_some_and(|message| message.fragments.contains_key(&number)) {
+            return Err(Error::Unknown("Duplicate fragment"));
+        }
+        if let (Some(known_total), Some(total)) = (known_total, total) {
+            if known_total != total {
+                return Err(Error::Unknown("Inconsistent total parameter"));
+            }
+        }
+        // The total may be learned after fragments with a higher number were stored
+        let total = total.or(known_total);
+        if let Some(total) = total {
+            if number > total
+                || known
+                    .is_some_and(|message| message.fragments.range(total + 1..).next().is_some())
+            {
+                return Err(Error::Unknown("Fragment number out of range"));
+            }
+        }
+
+        let message = self.messages.entry(id.to_string()).or_default();
+        message.total = total;
+        if number == 1 {
+            message.enclosing_header = Some(email.raw_header().to_vec());
+        }
+        message.fragments.insert(number, entity.value.to_vec());
+
+        match message.total {
+            Some(total)
+                if message.fragments.len() == total
+                    && message.fragments.keys().next_back() == Some(&total) => {}
+            _ => return Ok(None),
+        }
+
+        let message = self
+            .messages
+            .remove(id.as_ref())
+            .expect("the message has been inserted above");
+        let mut fragments = message.fragments.into_iter();
+        let (_, first_fragment) = fragments.next().expect("there is at least one fragment");
+        let (enclosed_header, body) =
+            crate::parsing::mime::partial::split_enclosed(&first_fragment);
+
+        let mut data = crate::parsing::mime::partial::merge_headers(
+            &message.enclosing_header.unwrap_or_default(),
+            enclosed_header,
+        );
+        data.extend_from_slice(b"\r\n");
+        data.extend_from_slice(body);
+        for (_, fragment) in fragments {
+            data.extend_from_slice(&fragment);
+        }
+
+        Ok(Some(data))
+    }
+}
+
+/// A file attached to an email.\
+/// Can be obtained with [Email::attachments].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Attachment<'a> {
+    /// The name of the file, taken from the Content-Disposition header or from the `name` parameter of the Content-Type header.\
+    /// It has been decoded and sanitized so that it does not contain any path separator, character forbidden on Windows or control character, nor a reserved device name.
+    /// However, it is still chosen by the sender so it should not be trusted.
+    pub filename: Option<Cow<'a, str>>,
+    pub content_type: ContentType<'a>,
+    /// The subtype (in lowercase).
+    pub subtype: Cow<'a, str>,
+    /// The exact size in bytes of the decoded data.
+    pub size: usize,
+    pub content_id: Option<(Cow<'a, str>, Cow<'a, str>)>,
+    #[cfg(feature = "content-disposition")]
+    pub disposition: Option<DispositionType<'a>>,
+    /// The decoded data of the file.
+    pub data: Cow<'a, [u8]>,
+}
+
+/// An iterator over the [attachments](Attachment) of an email.\
+/// Can be obtained with [Email::attachments].\
+/// Malformed multipart entities are skipped.
+#[derive(Debug, Clone)]
+pub struct Attachments<'a> {
+    pub(crate) stack: Vec<RawEntity<'a>>,
+}
+
+impl<'a> Iterator for Attachments<'a> {
+    type Item = Attachment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(entity) = self.stack.pop() {
+            if entity.mime_type == ContentType::Multipart {
+                if let Ok(parts) = crate::parsing::mime::multipart::parts(entity) {
+                    self.stack.extend(parts.into_iter().rev());
+                }
+            } else if let Some(attachment) = crate::parsing::mime::attachments::attachment(entity) {
+                return Some(attachment);
+            }
+        }
+
+        None
+    }
+}
+
+/// A structure resolving the references between the parts of a `multipart/related` entity.\
+/// Parts are indexed by Content-ID (for `cid:` URLs) and by Content-Location.\
+/// [Learn more](https://tools.ietf.org/html/rfc2387)
+///
+/// # Example
+///
+/// ```
+/// # use email_parser::prelude::*;
+/// # use email_parser::mime::RelatedResolver;
+/// let email = Email::parse(b"From: mubelotix@mubelotix.dev\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: multipart/related; boundary=related\r\n\r\n--related\r\nContent-Type: text/html\r\n\r\n<img src=\"cid:logo@mubelotix.dev\">\r\n--related\r\nContent-Type: image/png\r\nContent-ID: <logo@mubelotix.dev>\r\n\r\nPNG\r\n--related--\r\n").unwrap();
+/// let resolver = RelatedResolver::new(&email.mime_entity).unwrap();
+///
+/// let html = String::from_utf8_lossy(&resolver.root().value);
+/// assert_eq!(
+///     resolver.rewrite_html_with_data_uris(&html),
+///     "<img src=\"data:image/png;base64,UE5H\">"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RelatedResolver<'a> {
+    parts: Vec<RawEntity<'a>>,
+    root: usize,
+    base: Option<String>,
+}
+
+impl<'a> RelatedResolver<'a> {
+    /// Splits a `multipart/related` entity into its parts.\
+    /// The root part is selected according to the `start` and `type` parameters.\
+    /// An error is returned if the entity is not a valid `multipart/related` entity.
+    pub fn new(raw_entity: &'a RawEntity<'a>) -> Result<RelatedResolver<'a>, Error> {
+        if raw_entity.mime_type != ContentType::Multipart || raw_entity.subtype != "related" {
+            return Err(Error::Unknown("Not a multipart/related entity"));
+        }
+
+        let parts = crate::parsing::mime::multipart::parse_multipart(
+            raw_entity.value.as_ref(),
+            &raw_entity.parameters,
+        )?;
+        if parts.is_empty() {
+            return Err(Error::Unknown("Empty multipart/related entity"));
+        }
+
+        let root = crate::parsing::mime::related::root_index(
+            &parts,
+            raw_entity
+                .parameters
+                .get("start")
+                .map(|start| start.as_ref()),
+            raw_entity
+                .parameters
+                .get("type")
+                .map(|root_type| root_type.as_ref()),
+        );
+        let base = crate::parsing::mime::related::content_location(&parts[root])
+            .or_else(|| crate::parsing::mime::related::content_location(raw_entity));
+
+        Ok(RelatedResolver { parts, root, base })
+    }
+
+    /// The part to be processed first, usually the HTML document.
+    pub fn root(&self) -> &RawEntity<'a> {
+        &self.parts[self.root]
+    }
+
+    /// All the parts, including the root.
+    pub fn parts(&self) -> &[RawEntity<'a>] {
+        &self.parts
+    }
+
+    /// Finds the part referenced by a URL.\
+    /// It can be a `cid:` URL or a URL matching the Content-Location of a part, relative URLs being resolved against the location of the root.
+    pub fn resolve(&self, url: &str) -> Option<&RawEntity<'a>> {
+        crate::parsing::mime::related::resolve(&self.parts, self.base.as_deref(), url)
+            .map(|idx| &self.parts[idx])
+    }
+
+    /// Replaces the URLs of the HTML attributes referencing a part (`src`, `href`, `background`, `poster` and `data`).\
+    /// The closure receives the referenced part and returns the new URL.
+    /// When it returns `None`, the URL is left untouched.
+    pub fn rewrite_html<F>(&self, html: &str, mut f: F) -> String
+    where
+        F: FnMut(&RawEntity<'a>) -> Option<String>,
+    {
+        crate::parsing::mime::related::rewrite_html(html, |url| self.resolve(url).and_then(&mut f))
+    }
+
+    /// Replaces the URLs referencing a part by `data:` URIs containing the data of this part.\
+    /// This makes the HTML self-contained.
+    pub fn rewrite_html_with_data_uris(&self, html: &str) -> String {
+        self.rewrite_html(html, |part| {
+            Some(crate::parsing::mime::related::data_uri(part))
+        })
+    }
+}
+
+/// Options of the conversion of HTML to plain text.\
+/// The conversion drops scripts and styles, decodes character references, puts paragraphs and list items on their own lines, prefixes blockquotes with `>` and lists the targets of links as footnotes.
+///
+/// # Example
+///
+/// ```
+/// # use email_parser::mime::HtmlToText;
+/// let converter = HtmlToText::default();
+/// let html = "<p>Hello <a href=\"https://mubelotix.dev\">you</a>!</p><ul><li>First</li><li>Second</li></ul>";
+///
+/// assert_eq!(
+///     converter.convert(html),
+///     "Hello you[1]!\n\n* First\n* Second\n\n[1] https://mubelotix.dev"
+/// );
+/// assert_eq!(converter.snippet(html), "Hello you! First Second");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlToText {
+    /// Whether the targets of links should be listed as footnotes. Defaults to `true`.
+    pub link_footnotes: bool,
+    /// The maximum number of characters of a [snippet](HtmlToText::snippet), including the ellipsis. Defaults to `200`.
+    pub snippet_length: usize,
+}
+
+impl Default for HtmlToText {
+    fn default() -> HtmlToText {
+        HtmlToText {
+            link_footnotes: true,
+            snippet_length: 200,
+        }
+    }
+}
+
+impl HtmlToText {
+    pub fn new() -> HtmlToText {
+        HtmlToText::default()
+    }
+
+    /// Converts HTML to readable plain text.
+    pub fn convert(&self, html: &str) -> String {
+        crate::parsing::mime::html::convert(html, self)
+    }
+
+    /// Converts HTML to a single line of text suitable for previews.\
+    /// The text is truncated at a word boundary and an ellipsis is appended if it is longer than [snippet_length](HtmlToText::snippet_length).
+    pub fn snippet(&self, html: &str) -> String {
+        crate::parsing::mime::html::snippet(html, self)
+    }
+}
+
+/// An HTML sanitizer making `text/html` parts safe to display.\
+/// Scripts, styles, forms and frames are removed along with their content.
+/// Event handlers, URLs with unsafe schemes (such as `javascript:`) and, unless allowed, remote resources (such as tracking pixels) are removed too.
+/// Other tags and attributes are kept only if they are in the allow-lists.
+///
+/// # Example
+///
+/// ```
+/// # use email_parser::mime::HtmlSanitizer;
+/// let sanitizer = HtmlSanitizer::default();
+/// let (html, report) = sanitizer.sanitize("<p onclick=\"steal()\">Hi!</p><img src=\"https://tracker.example.com/pixel.gif\"><script>alert(1)</script>");
+///
+/// assert_eq!(html, "<p>Hi!</p><img>");
+/// assert_eq!(report.event_handlers, 1);
+/// assert_eq!(report.remote_resources, 1);
+/// assert_eq!(report.scripts, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlSanitizer {
+    /// The tags to keep (in lowercase).\
+    /// The content of the other tags is kept unless they are scripts, styles, forms or frames.
+    pub allowed_tags: HashSet<String>,
+    /// The attributes to keep (in lowercase).\
+    /// Event handlers (`on*` attributes) are always removed.
+    pub allowed_attributes: HashSet<String>,
+    /// The schemes allowed in URLs (in lowercase).\
+    /// `data:` URLs of images are always allowed in `src` attributes.
+    pub allowed_url_schemes: HashSet<String>,
+    /// Whether resources loaded automatically (such as images) can be fetched from remote servers. Defaults to `false`.
+    pub allow_remote_resources: bool,
+}
+
+impl Default for HtmlSanitizer {
+    fn default() -> HtmlSanitizer {
+        let set = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+
+        HtmlSanitizer {
+            allowed_tags: set(&[
+                "a",
+                "abbr",
+                "b",
+                "blockquote",
+                "br",
+                "caption",
+                "center",
+                "code",
+                "col",
+                "colgroup",
+                "dd",
+                "del",
+                "div",
+                "dl",
+                "dt",
+                "em",
+                "font",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "i",
+                "img",
+                "ins",
+                "li",
+                "ol",
+                "p",
+                "pre",
+                "q",
+                "s",
+                "small",
+                "span",
+                "strike",
+                "strong",
+                "sub",
+                "sup",
+                "table",
+                "tbody",
+                "td",
+                "tfoot",
+                "th",
+                "thead",
+                "tr",
+                "u",
+                "ul",
+            ]),
+            allowed_attributes: set(&[
+                "align",
+                "alt",
+                "bgcolor",
+                "border",
+                "cellpadding",
+                "cellspacing",
+                "cite",
+                "color",
+                "colspan",
+                "dir",
+                "face",
+                "height",
+                "href",
+                "lang",
+                "rowspan",
+                "size",
+                "src",
+                "start",
+                "title",
+                "valign",
+                "width",
+            ]),
+            allowed_url_schemes: set(&["http", "https", "mailto", "cid"]),
+            allow_remote_resources: false,
+        }
+    }
+}
+
+impl HtmlSanitizer {
+    pub fn new() -> HtmlSanitizer {
+        HtmlSanitizer::default()
+    }
+
+    /// Sanitizes decoded HTML (see [Entity::Text]).\
+    /// Returns the safe HTML and a report of what has been removed.
+    pub fn sanitize(&self, html: &str) -> (String, SanitizeReport) {
+        crate::parsing::mime::sanitizer::sanitize(html, self)
+    }
+}
+
+/// What an [HtmlSanitizer] has removed.\
+/// Everything is counted once per tag or attribute.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SanitizeReport {
+    /// `script` elements.
+    pub scripts: usize,
+    /// `on*` attributes of the kept tags.
+    pub event_handlers: usize,
+    /// URLs whose scheme is not allowed, such as `javascript:` URLs.
+    pub unsafe_urls: usize,
+    /// Blocked remote resources, such as tracking pixels.
+    pub remote_resources: usize,
+    /// `form` elements and form controls.
+    pub forms: usize,
+    /// `iframe`, `frame`, `object`, `embed` and `applet` elements.
+    pub frames: usize,
+    /// Other elements that are not allowed.
+    pub disallowed_tags: usize,
+    /// Other attributes that are not allowed.
+    pub disallowed_attributes: usize,
+}
+
+/// The `format=flowed` encoding of `text/plain` entities, in which long paragraphs are split into lines ending with a space.\
+/// [Learn more](https://tools.ietf.org/html/rfc3676)
+///
+/// # Example
+///
+/// ```
+/// # use email_parser::mime::Flowed;
+/// let flowed = Flowed {
+///     width: 20,
+///     ..Flowed::default()
+/// };
+/// let encoded = flowed.encode("The quick brown fox jumps over the lazy dog.\r\n");
+///
+/// assert_eq!(encoded, "The quick brown fox \r\njumps over the lazy \r\ndog.\r\n");
+/// assert_eq!(flowed.decode(&encoded), "The quick brown fox jumps over the lazy dog.\r\n");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flowed {
+    /// Whether the space ending flowed lines has been added by the encoder and must be removed when decoding (`delsp=yes`). Defaults to `false`.
+    pub delsp: bool,
+    /// The maximum length of encoded lines, when possible. Defaults to `78`.
+    pub width: usize,
+}
+
+impl Default for Flowed {
+    fn default() -> Flowed {
+        Flowed {
+            delsp: false,
+            width: 78,
+        }
+    }
+}
+
+impl Flowed {
+    pub fn new() -> Flowed {
+        Flowed::default()
+    }
+
+    /// Returns the options of a `text/plain; format=flowed` entity, or `None` if the entity is not flowed.
+    pub fn from_entity(raw_entity: &RawEntity) -> Option<Flowed> {
+        let is_flowed = raw_entity.mime_type == ContentType::Text
+            && raw_entity.subtype == "plain"
+            && raw_entity
+                .parameters
+                .get("format")
+                .map(|format| format.eq_ignore_ascii_case("flowed"))
+                .unwrap_or(false);
+        if !is_flowed {
+            return None;
+        }
+
+        Some(Flowed {
+            delsp: raw_entity
+                .parameters
+                .get("delsp")
+                .map(|delsp| delsp.eq_ignore_ascii_case("yes"))
+                .unwrap_or(false),
+            ..Flowed::default()
+        })
+    }
+
+    /// Unwraps flowed paragraphs and removes space-stuffing.\
+    /// Quote depths are preserved: quoted lines are prefixed by one `>` per level followed by a space.
+    pub fn decode(&self, text: &str) -> String {
+        crate::parsing::mime::flowed::decode(text, self.delsp)
+    }
+
+    /// Wraps long lines and space-stuffs lines when needed.\
+    /// The result uses CRLF line breaks and should be sent with the `format=flowed` parameter (and `delsp=yes` if [delsp](Flowed::delsp) is set).
+    pub fn encode(&self, text: &str) -> String {
+        crate::parsing::mime::flowed::encode(text, self.width, self.delsp)
+    }
+}
+
+/// The kind of a [TextSegment].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SegmentKind {
+    /// Text written by the sender of the message.
+    Content,
+    /// Text quoted from a previous message.\
+    /// It includes `>` prefixed lines, attribution lines (such as `On ... wrote:`) and everything following an Outlook `-----Original Message-----` separator.
+    Quote,
+    /// A signature, starting with the `-- ` delimiter.\
+    /// [Learn more](https://tools.ietf.org/html/rfc3676#section-4.3)
+    Signature,
+}
+
+/// A part of a decoded text body.\
+/// Can be obtained with [TextSegment::split].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextSegment {
+    pub kind: SegmentKind,
+    /// The byte range of this segment in the text.
+    pub range: std::ops::Range<usize>,
+}
+
+impl TextSegment {
+    /// Splits a decoded text body (see [Entity::Text]) into new content, quoted text and signatures.\
+    /// Segments are contiguous and cover the whole text.
+    /// Blank lines belong to the segment preceding them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use email_parser::mime::{SegmentKind, TextSegment};
+    /// let text = "Fine, thanks.\r\n\r\nOn Mon, 5 May 2003, Mubelotix wrote:\r\n> How are you?\r\n";
+    /// let segments = TextSegment::split(text);
+    ///
+    /// assert_eq!(segments[0].kind, SegmentKind::Content);
+    /// assert_eq!(&text[segments[0].range.clone()], "Fine, thanks.\r\n\r\n");
+    /// assert_eq!(segments[1].kind, SegmentKind::Quote);
+    /// ```
+    pub fn split(text: &str) -> Vec<TextSegment> {
+        crate::parsing::mime::segments::split(text)
+    }
+}
+
+/// A part of a header value, either plain text or decoded from encoded words.\
+/// Can be obtained with [HeaderSegment::parse] or [Email::header_segments].\
+/// [Learn more](https://tools.ietf.org/html/rfc2047)
+#[derive(Debug, PartialEq, Clone)]
+pub struct HeaderSegment<'a> {
+    /// The decoded text.
+    pub text: Cow<'a, str>,
+    /// The charset in which the text was encoded (in lowercase), or `None` for plain text.
+    pub charset: Option<Cow<'a, str>>,
+    /// The language of the text (in lowercase), if specified.\
+    /// [Learn more](https://tools.ietf.org/html/rfc2231#section-5)
+    pub language: Option<Cow<'a, str>>,
+}
+
+impl<'a> HeaderSegment<'a> {
+    /// Splits a raw unstructured header value (such as a subject) into segments.\
+    /// Whitespaces between adjacent encoded words are removed, just like when decoding the value, and adjacent segments with the same charset and language are merged.
+    /// Concatenating the texts of the segments gives the decoded value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use email_parser::mime::HeaderSegment;
+    /// let segments = HeaderSegment::parse(b"Re: =?utf-8*fr?Q?caf=C3=A9?=");
+    ///
+    /// assert_eq!(segments[0].text, "Re: ");
+    /// assert_eq!(segments[1].text, "café");
+    /// assert_eq!(segments[1].charset.as_ref().unwrap(), "utf-8");
+    /// assert_eq!(segments[1].language.as_ref().unwrap(), "fr");
+    /// ```
+    pub fn parse(value: &'a [u8]) -> Vec<HeaderSegment<'a>> {
+        crate::parsing::mime::encoded_headers::header_segments(value)
+    }
+}
+
+/// The place where encoded words are written in a header, which determines the characters that must be encoded.\
+/// [Learn more](https://tools.ietf.org/html/rfc2047#section-5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncodedWordContext {
+    /// An unstructured field such as the subject.
+    Unstructured,
+    /// A phrase such as the display name of a mailbox.\
+    /// Words that are not atoms (such as words containing a comma) are encoded too.
+    Phrase,
+    /// A comment, in which parentheses must be encoded.
+    Comment,
+}
+
+impl EncodedWordContext {
+    /// Encodes text so that it can be written in a header.\
+    /// Words that contain non-ASCII characters (or characters not allowed in this context) are converted to UTF-8 encoded words, consecutive ones being encoded together.
+    /// The shortest of the B and Q encodings is chosen, and encoded words are split so that they never exceed 75 characters nor split a character.
+    /// The result does not contain line breaks: it can be folded at any space.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use email_parser::mime::EncodedWordContext;
+    /// assert_eq!(
+    ///     EncodedWordContext::Unstructured.encode("Développement durable"),
+    ///     "=?utf-8?q?D=C3=A9veloppement?= durable"
+    /// );
+    /// assert_eq!(
+    ///     EncodedWordContext::Phrase.encode("Doe, John"),
+    ///     "=?utf-8?q?Doe=2C?= John"
+    /// );
+    /// ```
+    pub fn encode(&self, text: &str) -> String {
+        crate::parsing::mime::encoded_headers::encode(text, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejected_fragment_is_not_stored() {
+        let mut reassembler = PartialReassembler::new();
+        let fragment = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: message/partial; id=\"ABC@host.com\"; number=3; total=2\r\n\r\nBody\r\n").unwrap();
+        assert!(reassembler.add(&fragment).is_err());
+        assert!(reassembler.messages.is_empty());
+    }
+}
@@ -2,14 +2,14 @@ use crate::address::*;
 use crate::prelude::*;
 use std::borrow::Cow;
 
-pub fn message_id(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
-    fn no_fold_litteral(input: &[u8]) -> Res<Cow<str>> {
+pub fn message_id<const UTF8: bool>(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
+    fn no_fold_litteral<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
         let (input, ()) = tag(
             input,
             b"[",
             "TAG ERROR: In message_id, a no_fold_litteral domain must be preceded by a `[`.",
         )?;
-        let (input, domain) = take_while(input, is_dtext)?;
+        let (input, domain) = take_while(input, is_dtext::<UTF8>)?;
         let (input, ()) = tag(
             input,
             b"]",
@@ -18,76 +18,104 @@ pub fn message_id(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
         Ok((input, Cow::Borrowed(domain)))
     }
 
-    let (input, _cfws) = optional(input, cfws);
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
     let (input, ()) = tag(
         input,
         b"<",
         "TAG ERROR: A message ID must start with a `<`.",
     )?;
-    let (input, id_left) = dot_atom_text(input)?;
+    let (input, id_left) = dot_atom_text::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"@",
         "TAG ERROR: A message ID right part must be followed by a `@`.",
     )?;
-    let (input, id_right) = match_parsers(input, &mut [dot_atom_text, no_fold_litteral][..])?;
+    let (input, id_right) = match_parsers(
+        input,
+        &mut [dot_atom_text::<UTF8>, no_fold_litteral::<UTF8>][..],
+    )?;
     let (input, ()) = tag(
         input,
         b">",
         "TAG ERROR: A message ID left part must be followed by a `>`.",
     )?;
-    let (input, _cfws) = optional(input, cfws);
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
 
     Ok((input, (id_left, id_right)))
 }
 
-pub fn addr_spec(input: &[u8]) -> Res<EmailAddress> {
-    let (input, local_part) = local_part(input)?;
+pub fn addr_spec<const UTF8: bool>(input: &[u8]) -> Res<EmailAddress> {
+    let (input, local_part) = local_part::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"@",
         "TAG ERROR: An address local part must be followed by a `@`.",
     )?;
-    let (input, domain) = domain(input)?;
+    let (input, domain) = address_domain::<UTF8>(input)?;
     Ok((input, EmailAddress { local_part, domain }))
 }
 
-pub fn angle_addr(input: &[u8]) -> Res<EmailAddress> {
-    let (input, _cfws) = optional(input, cfws);
+pub fn angle_addr<const UTF8: bool>(input: &[u8]) -> Res<EmailAddress> {
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
     let (input, ()) = tag(
         input,
         b"<",
         "TAG ERROR: A angle_addr must start with a `<`.",
     )?;
-    let (input, addr_spec) = addr_spec(input)?;
+    let (input, addr_spec) = addr_spec::<UTF8>(input)?;
     let (input, ()) = tag(input, b">", "TAG ERROR: A angle_addr must end with a `>`.")?;
-    let (input, _cfws) = optional(input, cfws);
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
     Ok((input, addr_spec))
 }
 
-pub fn name_addr(input: &[u8]) -> Res<Mailbox> {
-    let (input, display_name) = optional(input, phrase);
-    let (input, angle_addr) = angle_addr(input)?;
+pub fn name_addr<const UTF8: bool>(input: &[u8]) -> Res<Mailbox> {
+    let (input, display_name) = optional(input, phrase::<UTF8>);
+    let (input, angle_addr) = angle_addr::<UTF8>(input)?;
+    let (name, name_separators) = match display_name {
+        Some((name, separators)) => (Some(name), separators),
+        None => (None, Vec::new()),
+    };
 
     Ok((
         input,
         Mailbox {
-            name: display_name,
+            name,
+            name_separators,
             address: angle_addr,
+            comments: Vec::new(),
+            trailing_comment: false,
         },
     ))
 }
 
-pub fn local_part(input: &[u8]) -> Res<Cow<str>> {
-    match_parsers(input, &mut [dot_atom, quoted_string][..])
+pub fn local_part<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
+    match_parsers(input, &mut [dot_atom::<UTF8>, quoted_string::<UTF8>][..])
+}
+
+pub fn domain<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
+    match_parsers(input, &mut [dot_atom::<UTF8>, domain_literal::<UTF8>][..])
 }
 
-pub fn domain(input: &[u8]) -> Res<Cow<str>> {
-    match_parsers(input, &mut [dot_atom, domain_literal][..])
+/// Parses the domain of an address, telling host names and the different kinds of address literals apart.\
+/// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.1.3)
+pub fn address_domain<const UTF8: bool>(input: &[u8]) -> Res<'_, Domain<'_>> {
+    if let Ok((input, name)) = dot_atom::<UTF8>(input) {
+        return Ok((input, Domain::Name(name)));
+    }
+    let (input, literal) = domain_literal::<UTF8>(input)?;
+    if let Some(address) = ipv4_literal(&literal) {
+        Ok((input, Domain::Ipv4(address)))
+    } else if let Some(address) = ipv6_literal(&literal) {
+        Ok((input, Domain::Ipv6(address)))
+    } else {
+        Ok((input, Domain::Literal(literal)))
+    }
 }
 
-pub fn domain_literal<'a>(input: &'a [u8]) -> Res<Cow<'a, str>> {
-    let (input, _cfws) = optional(input, cfws);
+/// Parses a domain literal and returns its content, without the brackets.\
+/// Folding whitespaces are removed, as address literals cannot contain any.
+pub fn domain_literal<'a, const UTF8: bool>(input: &'a [u8]) -> Res<Cow<'a, str>> {
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
     let (mut input, ()) = tag(
         input,
         b"[",
@@ -96,9 +124,8 @@ pub fn domain_literal<'a>(input: &'a [u8]) -> Res<Cow<'a, str>> {
     let mut output = empty_string();
     loop {
         let (new_input, _fws) = optional(input, fws);
-        if let Ok((new_input, text)) = take_while1(new_input, is_dtext) {
+        if let Ok((new_input, text)) = take_while1(new_input, is_dtext::<UTF8>) {
             input = new_input;
-            //add_string(&mut output, fws); should it be added?
             add_str(&mut output, text);
         } else {
             break;
@@ -110,36 +137,90 @@ pub fn domain_literal<'a>(input: &'a [u8]) -> Res<Cow<'a, str>> {
         b"]",
         "TAG ERROR: A domain litteral must be followed by a `]`.",
     )?;
-    let (input, _cfws) = optional(input, cfws);
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
     Ok((input, output))
 }
 
-pub fn mailbox(input: &[u8]) -> Res<Mailbox> {
-    match_parsers(
+/// Checks an IPv4 address literal, like `192.0.2.1`.\
+/// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.1.3)
+pub fn ipv4_literal(literal: &str) -> Option<std::net::Ipv4Addr> {
+    let mut octets = [0; 4];
+    let mut parts = literal.split('.');
+    for octet in octets.iter_mut() {
+        let part = parts.next()?;
+        if part.is_empty() || part.len() > 3 || !part.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        *octet = part.parse().ok()?;
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(std::net::Ipv4Addr::from(octets))
+}
+
+/// Checks an IPv6 address literal, like `IPv6:2001:db8::1`.\
+/// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.1.3)
+pub fn ipv6_literal(literal: &str) -> Option<std::net::Ipv6Addr> {
+    let (tag, address) = literal.split_at(literal.get(..5)?.len());
+    if !tag.eq_ignore_ascii_case("IPv6:") {
+        return None;
+    }
+    address.parse().ok()
+}
+
+/// Checks a general address literal, made of a standardized tag and of its content, like `tag:content`.\
+/// [Learn more](https://tools.ietf.org/html/rfc5321#section-4.1.3)
+pub fn general_literal(literal: &str) -> Option<(&str, &str)> {
+    let (tag, content) = literal.split_once(':')?;
+    let is_ldh = !tag.is_empty()
+        && tag.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-')
+        && !tag.ends_with('-');
+    let is_dcontent = !content.is_empty()
+        && content
+            .bytes()
+            .all(|c| (33..=90).contains(&c) || (94..=126).contains(&c));
+    if is_ldh && is_dcontent {
+        Some((tag, content))
+    } else {
+        None
+    }
+}
+
+pub fn mailbox<const UTF8: bool>(input: &[u8]) -> Res<Mailbox> {
+    let (rest, mut mailbox) = match_parsers(
         input,
         &mut [
-            name_addr,
+            name_addr::<UTF8>,
             (|input| {
-                addr_spec(input).map(|(i, m)| {
+                addr_spec::<UTF8>(input).map(|(i, m)| {
                     (
                         i,
                         Mailbox {
                             name: None,
+                            name_separators: Vec::new(),
                             address: m,
+                            comments: Vec::new(),
+                            trailing_comment: false,
                         },
                     )
                 })
             }) as fn(input: &[u8]) -> Res<Mailbox>,
         ][..],
-    )
+    )?;
+    let (comments, trailing) = comments_and_trailing::<UTF8>(&input[..input.len() - rest.len()]);
+    mailbox.comments = comments;
+    mailbox.trailing_comment = trailing > 0;
+
+    Ok((rest, mailbox))
 }
 
-pub fn mailbox_list(input: &[u8]) -> Res<Vec<Mailbox>> {
+pub fn mailbox_list<const UTF8: bool>(input: &[u8]) -> Res<Vec<Mailbox>> {
     let mut mailboxes = Vec::new();
-    let (mut input, first_mailbox) = mailbox(input)?;
+    let (mut input, first_mailbox) = mailbox::<UTF8>(input)?;
     mailboxes.push(first_mailbox);
 
-    while let Ok((new_input, new_mailbox)) = prefixed(input, mailbox, ",") {
+    while let Ok((new_input, new_mailbox)) = prefixed(input, mailbox::<UTF8>, ",") {
         input = new_input;
         mailboxes.push(new_mailbox);
     }
@@ -147,18 +228,18 @@ pub fn mailbox_list(input: &[u8]) -> Res<Vec<Mailbox>> {
     Ok((input, mailboxes))
 }
 
-pub fn group(input: &[u8]) -> Res<(Vec<Cow<str>>, Vec<Mailbox>)> {
-    let (input, display_name) = phrase(input)?;
+pub fn group<const UTF8: bool>(input: &[u8]) -> Res<'_, Group<'_>> {
+    let (input, (name, name_separators)) = phrase::<UTF8>(input)?;
     let (mut input, ()) = tag(
         input,
         b":",
         "TAG ERROR: A group display name must be followed by a `:`.",
     )?;
 
-    let group_list = if let Ok((new_input, mailbox_list)) = mailbox_list(input) {
+    let mailboxes = if let Ok((new_input, mailbox_list)) = mailbox_list::<UTF8>(input) {
         input = new_input;
         mailbox_list
-    } else if let Ok((new_input, _cfws)) = cfws(input) {
+    } else if let Ok((new_input, _cfws)) = cfws::<UTF8>(input) {
         input = new_input;
         Vec::new()
     } else {
@@ -170,26 +251,33 @@ pub fn group(input: &[u8]) -> Res<(Vec<Cow<str>>, Vec<Mailbox>)> {
         b";",
         "TAG ERROR: A group mailbox list must be closed by a `;`.",
     )?;
-    let (input, _cfws) = optional(input, cfws);
-    Ok((input, (display_name, group_list)))
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
+    Ok((
+        input,
+        Group {
+            name,
+            name_separators,
+            mailboxes,
+        },
+    ))
 }
 
-pub fn address(input: &[u8]) -> Res<Address> {
-    if let Ok((input, mailbox)) = mailbox(input) {
+pub fn address<const UTF8: bool>(input: &[u8]) -> Res<Address> {
+    if let Ok((input, mailbox)) = mailbox::<UTF8>(input) {
         Ok((input, Address::Mailbox(mailbox)))
-    } else if let Ok((input, group)) = group(input) {
+    } else if let Ok((input, group)) = group::<UTF8>(input) {
         Ok((input, Address::Group(group)))
     } else {
         Err(Error::Unknown("Invalid address: not a mailbox nor a group"))
     }
 }
 
-pub fn address_list(input: &[u8]) -> Res<Vec<Address>> {
+pub fn address_list<const UTF8: bool>(input: &[u8]) -> Res<Vec<Address>> {
     let mut addresses = Vec::new();
-    let (mut input, first_address) = address(input)?;
+    let (mut input, first_address) = address::<UTF8>(input)?;
     addresses.push(first_address);
 
-    while let Ok((new_input, new_address)) = prefixed(input, address, ",") {
+    while let Ok((new_input, new_address)) = prefixed(input, address::<UTF8>, ",") {
         input = new_input;
         addresses.push(new_address);
     }
@@ -203,87 +291,161 @@ mod tests {
 
     #[test]
     fn test_local_part() {
-        assert_eq!(local_part(b"mubelotix").unwrap().1, "mubelotix");
+        assert_eq!(local_part::<false>(b"mubelotix").unwrap().1, "mubelotix");
         assert_eq!(
-            local_part(b"\"mubelotix\\ the\\ admin\"").unwrap().1,
+            local_part::<false>(b"\"mubelotix\\ the\\ admin\"")
+                .unwrap()
+                .1,
             "mubelotix the admin"
         );
     }
 
     #[test]
     fn test_message_id() {
-        assert_eq!(message_id(b"<idleft@idright>").unwrap().1 .0, "idleft");
-        assert_eq!(message_id(b"<idleft@idright>").unwrap().1 .1, "idright");
-        assert_eq!(message_id(b"<idleft@[idright]>").unwrap().1 .1, "idright");
+        assert_eq!(
+            message_id::<false>(b"<idleft@idright>").unwrap().1 .0,
+            "idleft"
+        );
+        assert_eq!(
+            message_id::<false>(b"<idleft@idright>").unwrap().1 .1,
+            "idright"
+        );
+        assert_eq!(
+            message_id::<false>(b"<idleft@[idright]>").unwrap().1 .1,
+            "idright"
+        );
     }
 
     #[test]
     fn test_domain() {
         assert_eq!(
-            domain_literal(b"[mubelotix.dev]").unwrap().1,
+            domain_literal::<false>(b"[mubelotix.dev]").unwrap().1,
+            "mubelotix.dev"
+        );
+        assert_eq!(
+            domain_literal::<false>(b"[mubelotix\r\n .dev]").unwrap().1,
+            "mubelotix.dev"
+        );
+
+        assert_eq!(
+            domain::<false>(b"[mubelotix\r\n .dev]").unwrap().1,
             "mubelotix.dev"
         );
         assert_eq!(
-            domain_literal(b"[mubelotix\r\n .dev]").unwrap().1,
+            domain::<false>(b"mubelotix.dev").unwrap().1,
             "mubelotix.dev"
         );
 
-        assert_eq!(domain(b"[mubelotix\r\n .dev]").unwrap().1, "mubelotix.dev");
-        assert_eq!(domain(b"mubelotix.dev").unwrap().1, "mubelotix.dev");
+        assert_eq!(
+            address_domain::<false>(b"mubelotix.dev").unwrap().1,
+            Domain::Name(Cow::Borrowed("mubelotix.dev"))
+        );
+        assert_eq!(
+            address_domain::<false>(b"[192.168.0.1]").unwrap().1,
+            Domain::Ipv4(std::net::Ipv4Addr::new(192, 168, 0, 1))
+        );
+        assert_eq!(
+            address_domain::<false>(b"[IPv6:::1]").unwrap().1,
+            Domain::Ipv6(std::net::Ipv6Addr::LOCALHOST)
+        );
+        assert_eq!(
+            address_domain::<false>(b"[x-tag:content]").unwrap().1,
+            Domain::Literal(Cow::Borrowed("x-tag:content"))
+        );
+        assert_eq!(
+            address_domain::<false>(b"[192.168.0.300]").unwrap().1,
+            Domain::Literal(Cow::Borrowed("192.168.0.300"))
+        );
     }
 
     #[test]
     fn test_addr() {
-        let address = addr_spec(b"mubelotix@mubelotix.dev").unwrap().1;
+        let address = addr_spec::<false>(b"mubelotix@mubelotix.dev").unwrap().1;
         assert_eq!(address.local_part, "mubelotix");
         assert_eq!(address.domain, "mubelotix.dev");
 
-        let address = addr_spec(b"\"special\\ person\"@gmail.com").unwrap().1;
+        let address = addr_spec::<false>(b"\"special\\ person\"@gmail.com")
+            .unwrap()
+            .1;
         assert_eq!(address.local_part, "special person");
         assert_eq!(address.domain, "gmail.com");
 
-        let mlbx = name_addr(b"<mubelotix@gmail.com>").unwrap().1;
+        let mlbx = name_addr::<false>(b"<mubelotix@gmail.com>").unwrap().1;
         assert!(mlbx.name.is_none());
         assert_eq!(mlbx.address.local_part, "mubelotix");
         assert_eq!(mlbx.address.domain, "gmail.com");
 
-        let mlbx = name_addr(b"Random Guy <someone@gmail.com>").unwrap().1;
+        let mlbx = name_addr::<false>(b"Random Guy <someone@gmail.com>")
+            .unwrap()
+            .1;
         assert_eq!(mlbx.name.unwrap().len(), 2);
         assert_eq!(mlbx.address.local_part, "someone");
         assert_eq!(mlbx.address.domain, "gmail.com");
 
-        let mlbx = mailbox(b"mubelotix@mubelotix.dev").unwrap().1;
+        let mlbx = mailbox::<false>(b"mubelotix@mubelotix.dev").unwrap().1;
         assert!(mlbx.name.is_none());
         assert_eq!(mlbx.address.local_part, "mubelotix");
         assert_eq!(mlbx.address.domain, "mubelotix.dev");
 
-        let mlbx = mailbox(b"Random Guy <someone@gmail.com>").unwrap().1;
+        let mlbx = mailbox::<false>(b"Random Guy <someone@gmail.com>")
+            .unwrap()
+            .1;
         assert_eq!(mlbx.name.unwrap(), vec!["Random", "Guy"]);
         assert_eq!(mlbx.address.local_part, "someone");
         assert_eq!(mlbx.address.domain, "gmail.com");
     }
 
+    #[test]
+    fn test_address_literals() {
+        assert_eq!(
+            ipv4_literal("192.0.2.001"),
+            Some(std::net::Ipv4Addr::new(192, 0, 2, 1))
+        );
+        assert!(ipv4_literal("192.0.2.256").is_none());
+        assert!(ipv4_literal("192.0.2").is_none());
+        assert!(ipv4_literal("192.0.2.1.5").is_none());
+
+        assert_eq!(
+            ipv6_literal("IPv6:::1"),
+            Some(std::net::Ipv6Addr::LOCALHOST)
+        );
+        assert!(ipv6_literal("ipv6:2001:db8::192.0.2.1").is_some());
+        assert!(ipv6_literal("IPv6:2001:db8:::1").is_none());
+        assert!(ipv6_literal("::1").is_none());
+
+        assert_eq!(general_literal("x-tag:content"), Some(("x-tag", "content")));
+        assert!(general_literal("x-tag:").is_none());
+        assert!(general_literal("tag-:content").is_none());
+    }
+
     #[test]
     fn test_lists() {
         assert_eq!(
-            mailbox_list(b"test@gmail.com,Michel<michel@gmail.com>,<postmaster@mubelotix.dev>")
-                .unwrap()
-                .1
-                .len(),
+            mailbox_list::<false>(
+                b"test@gmail.com,Michel<michel@gmail.com>,<postmaster@mubelotix.dev>"
+            )
+            .unwrap()
+            .1
+            .len(),
             3
         );
 
-        let (name, list) =
-            group(b"Developers: Mubelotix <mubelotix@mubelotix.dev>, Someone <guy@gmail.com>;")
-                .unwrap()
-                .1;
+        let Group {
+            name,
+            mailboxes: list,
+            ..
+        } = group::<false>(
+            b"Developers: Mubelotix <mubelotix@mubelotix.dev>, Someone <guy@gmail.com>;",
+        )
+        .unwrap()
+        .1;
         assert_eq!(name[0], "Developers");
         assert_eq!(list[0].name.as_ref().unwrap(), &vec!["Mubelotix"]);
         assert_eq!(list[0].address.local_part, "mubelotix");
         assert_eq!(list[0].address.domain, "mubelotix.dev");
 
         assert_eq!(
-            address_list(
+            address_list::<false>(
                 b"mubelotix@gmail.com,guy@gmail.com,Developers:mubelotix@gmail.com,guy@gmail.com;"
             )
             .unwrap()
@@ -292,4 +454,27 @@ mod tests {
             3
         );
     }
+
+    #[test]
+    fn test_utf8_addresses() {
+        let mlbx = mailbox::<true>("José <josé@exämple.de>".as_bytes())
+            .unwrap()
+            .1;
+        assert_eq!(mlbx.name.unwrap(), vec!["José"]);
+        assert_eq!(mlbx.address.local_part, "josé");
+        assert_eq!(mlbx.address.domain, "exämple.de");
+
+        let address = addr_spec::<true>("\"Jos\\é Ω\"@[exämple.de]".as_bytes())
+            .unwrap()
+            .1;
+        assert_eq!(address.local_part, "José Ω");
+        assert_eq!(address.domain, Domain::Literal(Cow::Borrowed("exämple.de")));
+
+        // UTF-8 is only accepted when enabled
+        assert!(addr_spec::<false>("josé@exämple.de".as_bytes()).is_err());
+
+        // Invalid UTF-8 is rejected
+        assert!(addr_spec::<true>(b"jos\xC3@example.de").is_err());
+        assert!(addr_spec::<true>(b"jose@ex\xFFample.de").unwrap().0 != b"");
+    }
 }
@@ -5,16 +5,30 @@ pub fn is_wsp(character: u8) -> bool {
     character == 9 || character == 32
 }
 
+/// Whether header fields accept UTF-8 by default, as enabled by the `utf8-headers` feature.\
+/// Header parsers take this as their `UTF8` parameter, except for `message/global` entities, whose header fields can always contain UTF-8.\
+/// [Learn more](https://tools.ietf.org/html/rfc6532#section-3.7)
+pub const UTF8_HEADERS: bool = cfg!(feature = "utf8-headers");
+
+/// Returns `true` if the byte is part of a non-ASCII UTF-8 sequence and `UTF8` allows UTF-8 in header fields.\
+/// Parsers producing strings from such bytes must validate them.\
+/// [Learn more](https://tools.ietf.org/html/rfc6532#section-3.1)
 #[inline]
-pub fn is_ctext(character: u8) -> bool {
+pub fn is_utf8_non_ascii<const UTF8: bool>(c: u8) -> bool {
+    UTF8 && c >= 0x80
+}
+
+#[inline]
+pub fn is_ctext<const UTF8: bool>(character: u8) -> bool {
     (character >= 33 && character <= 39)
         || (character >= 42 && character <= 91)
         || (character >= 93 && character <= 126)
+        || is_utf8_non_ascii::<UTF8>(character)
 }
 
 #[inline]
-pub fn is_vchar(character: u8) -> bool {
-    character >= 0x21 && character <= 0x7e
+pub fn is_vchar<const UTF8: bool>(character: u8) -> bool {
+    (character >= 0x21 && character <= 0x7e) || is_utf8_non_ascii::<UTF8>(character)
 }
 
 #[inline]
@@ -51,12 +65,12 @@ pub fn two_digits(input: &[u8]) -> Result<(&[u8], u8), Error> {
 }
 
 #[inline]
-pub fn is_dtext(c: u8) -> bool {
-    (c >= 33 && c <= 90) || (c >= 94 && c <= 126)
+pub fn is_dtext<const UTF8: bool>(c: u8) -> bool {
+    (c >= 33 && c <= 90) || (c >= 94 && c <= 126) || is_utf8_non_ascii::<UTF8>(c)
 }
 
 #[inline]
-pub fn is_atext(c: u8) -> bool {
+pub fn is_atext<const UTF8: bool>(c: u8) -> bool {
     is_alpha(c)
         || is_digit(c)
         || c == b'!'
@@ -78,6 +92,7 @@ pub fn is_atext(c: u8) -> bool {
         || c == b'|'
         || c == b'}'
         || c == b'~'
+        || is_utf8_non_ascii::<UTF8>(c)
 }
 
 #[inline]
@@ -117,8 +132,8 @@ pub fn tspecial(c: u8) -> bool {
 }
 
 #[inline]
-pub fn is_qtext(c: u8) -> bool {
-    (c >= 35 && c <= 126 && c != 92) || c == 33
+pub fn is_qtext<const UTF8: bool>(c: u8) -> bool {
+    (c >= 35 && c <= 126 && c != 92) || c == 33 || is_utf8_non_ascii::<UTF8>(c)
 }
 
 #[inline]
@@ -142,4 +157,16 @@ mod test {
         assert!(!is_wsp(b'a'));
         assert!(!is_wsp(b'e'));
     }
+
+    #[test]
+    fn test_utf8_non_ascii() {
+        assert!(is_atext::<false>(b'a'));
+        assert!(!is_atext::<true>(b'@'));
+        assert!(!is_atext::<false>(0xC3));
+        assert!(is_atext::<true>(0xC3));
+        assert!(is_qtext::<true>(0xA9));
+        assert!(is_dtext::<true>(0xE2));
+        assert!(!is_vchar::<false>(0x80));
+        assert!(is_vchar::<true>(0x80));
+    }
 }
@@ -89,14 +89,11 @@ where
     for i in 0..input.len() {
         unsafe {
             if !condition(*input.get_unchecked(i)) {
-                return Ok((
-                    input.get_unchecked(i..),
-                    std::str::from_utf8_unchecked(input.get_unchecked(..i)),
-                ));
+                return split_str(input, i);
             }
         }
     }
-    Ok((&[], unsafe { std::str::from_utf8_unchecked(input) }))
+    split_str(input, input.len())
 }
 
 #[inline]
@@ -117,14 +114,40 @@ where
     for i in 1..input.len() {
         unsafe {
             if !condition(*input.get_unchecked(i)) {
-                return Ok((
-                    input.get_unchecked(i..),
-                    std::str::from_utf8_unchecked(input.get_unchecked(..i)),
-                ));
+                return non_empty(split_str(input, i)?);
             }
         }
     }
-    Ok((&[], unsafe { std::str::from_utf8_unchecked(input) }))
+    non_empty(split_str(input, input.len())?)
+}
+
+#[inline]
+fn non_empty<'a>((input, output): (&'a [u8], &'a str)) -> Res<'a, &'a str> {
+    if output.is_empty() {
+        return Err(Error::Unknown("Invalid UTF-8 sequence"));
+    }
+    Ok((input, output))
+}
+
+/// Splits the input at `idx`, converting the first part to a string.\
+/// The string is truncated before the first invalid UTF-8 sequence, which only character sets accepting UTF-8 can match.
+#[inline]
+fn split_str(input: &[u8], idx: usize) -> Res<'_, &str> {
+    let idx = match std::str::from_utf8(&input[..idx]) {
+        Ok(_) => idx,
+        Err(e) => e.valid_up_to(),
+    };
+    // JUSTIFICATION
+    //  Benefit
+    //      Avoid validating the string a second time.
+    //  Correctness
+    //      The bytes before `idx` have been checked above.
+    unsafe {
+        Ok((
+            input.get_unchecked(idx..),
+            std::str::from_utf8_unchecked(input.get_unchecked(..idx)),
+        ))
+    }
 }
 
 #[inline]
@@ -14,28 +14,28 @@ pub fn lowercase(mut value: Cow<str>) -> Cow<str> {
     value
 }
 
-pub fn atom(mut input: &[u8]) -> Res<&str> {
-    if let Ok((new_input, _)) = cfws(input) {
+pub fn atom<const UTF8: bool>(mut input: &[u8]) -> Res<&str> {
+    if let Ok((new_input, _)) = cfws::<UTF8>(input) {
         input = new_input
     }
     let (mut input, atom) =
-        take_while1(input, is_atext).map_err(|_| Error::Unknown("Atom required"))?;
-    if let Ok((new_input, _)) = cfws(input) {
+        take_while1(input, is_atext::<UTF8>).map_err(|_| Error::Unknown("Atom required"))?;
+    if let Ok((new_input, _)) = cfws::<UTF8>(input) {
         input = new_input
     }
     Ok((input, atom))
 }
 
-pub fn dot_atom_text(input: &[u8]) -> Res<Cow<str>> {
-    let (mut input, output) = take_while1(input, is_atext)?;
+pub fn dot_atom_text<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
+    let (mut input, output) = take_while1(input, is_atext::<UTF8>)?;
     let mut output = Cow::Borrowed(output);
 
     loop {
         if input.starts_with(b".") {
             if let Ok((new_input, atom)) = if cfg!(feature = "compatibility-fixes") {
-                take_while(&input[1..], is_atext)
+                take_while(&input[1..], is_atext::<UTF8>)
             } else {
-                take_while1(&input[1..], is_atext)
+                take_while1(&input[1..], is_atext::<UTF8>)
             } {
                 add_string(&mut output, from_slice(&input[..1]));
                 input = new_input;
@@ -51,64 +51,91 @@ pub fn dot_atom_text(input: &[u8]) -> Res<Cow<str>> {
     Ok((input, output))
 }
 
-pub fn dot_atom(mut input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
-    if let Ok((new_input, _)) = cfws(input) {
+pub fn dot_atom<const UTF8: bool>(mut input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
+    if let Ok((new_input, _)) = cfws::<UTF8>(input) {
         input = new_input
     }
-    let (mut input, dot_atom) = dot_atom_text(input)?;
-    if let Ok((new_input, _)) = cfws(input) {
+    let (mut input, dot_atom) = dot_atom_text::<UTF8>(input)?;
+    if let Ok((new_input, _)) = cfws::<UTF8>(input) {
         input = new_input
     }
     Ok((input, dot_atom))
 }
 
-pub fn word(input: &[u8]) -> Res<Cow<str>> {
+pub fn word<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
     match_parsers(
         input,
         &mut [
             |input| {
-                let (input, value) = atom(input)?;
+                let (input, value) = atom::<UTF8>(input)?;
                 Ok((input, Cow::Borrowed(value)))
             },
-            |input| quoted_string(input),
+            |input| quoted_string::<UTF8>(input),
         ][..],
     )
 }
 
-pub fn phrase(input: &[u8]) -> Result<(&[u8], Vec<Cow<str>>), Error> {
+/// Parses a phrase, returning its words and the whitespaces found between them.\
+/// Separators are unfolded and comments are removed from them.
+/// Adjacent encoded words are merged because the whitespaces between them are ignored.\
+/// [Learn more](https://tools.ietf.org/html/rfc2047#section-6.2)
+pub fn phrase<const UTF8: bool>(input: &[u8]) -> Res<'_, (Vec<Cow<'_, str>>, Vec<Cow<'_, str>>)> {
+    /// Parses an atom or a quoted string, without the surrounding whitespaces and comments.
+    fn bare_word<const UTF8: bool>(input: &[u8]) -> Res<'_, Cow<'_, str>> {
+        if let Ok((input, atom)) = take_while1(input, is_atext::<UTF8>) {
+            return Ok((input, Cow::Borrowed(atom)));
+        }
+        bare_quoted_string::<UTF8>(input)
+    }
+
+    /// Returns the word and whether it was an encoded word.
     #[cfg(feature = "mime")]
-    fn word(input: &[u8]) -> Res<Cow<str>> {
-        match_parsers(
-            input,
-            &mut [
-                (|i| {
-                    let (i, _) = optional(i, fws);
-                    crate::parsing::mime::encoded_headers::encoded_word(i)
-                }),
-                (|i| crate::parsing::common::word(i)),
-            ][..],
-        )
+    fn word<const UTF8: bool>(input: &[u8]) -> Res<'_, (Cow<'_, str>, bool)> {
+        if let Ok((i, text)) = crate::parsing::mime::encoded_headers::encoded_word(input) {
+            return Ok((i, (text, true)));
+        }
+        bare_word::<UTF8>(input).map(|(i, word)| (i, (word, false)))
     }
 
-    let mut words = Vec::new();
-    let (mut input, first_word) = word(input)?;
-    words.push(first_word);
+    #[cfg(not(feature = "mime"))]
+    fn word<const UTF8: bool>(input: &[u8]) -> Res<'_, (Cow<'_, str>, bool)> {
+        bare_word::<UTF8>(input).map(|(i, word)| (i, (word, false)))
+    }
 
-    while let Ok((new_input, word)) = word(input) {
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
+    let (mut input, (first_word, mut previous_was_encoded)) = word::<UTF8>(input)?;
+    let mut words = vec![first_word];
+    let mut separators = Vec::new();
+
+    loop {
+        let (new_input, separator) = optional(input, cfws::<UTF8>);
+        let (new_input, (word, is_encoded)) = match word::<UTF8>(new_input) {
+            Ok(result) => result,
+            Err(_) => break,
+        };
         input = new_input;
-        words.push(word)
+        match words.last_mut() {
+            // Whitespaces between adjacent encoded words are ignored.
+            Some(last) if is_encoded && previous_was_encoded => add_string(last, word),
+            _ => {
+                separators.push(separator.unwrap_or(Cow::Borrowed("")));
+                words.push(word);
+            }
+        }
+        previous_was_encoded = is_encoded;
     }
 
-    Ok((input, words))
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
+    Ok((input, (words, separators)))
 }
 
-pub fn unstructured(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
+pub fn unstructured<const UTF8: bool>(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
     let (mut input, output) = collect_many(input, |i| {
         collect_pair(
             i,
             |i| Ok(fws(i).unwrap_or((i, empty_string()))),
             |i| {
-                let (input, value) = take_while1(i, is_vchar)?;
+                let (input, value) = take_while1(i, is_vchar::<UTF8>)?;
                 Ok((input, Cow::Borrowed(value)))
             },
         )
@@ -122,7 +149,7 @@ pub fn unstructured(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
 }
 
 #[cfg(feature = "mime")]
-pub fn mime_unstructured(input: &[u8]) -> Res<Cow<str>> {
+pub fn mime_unstructured<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
     let mut previous_was_encoded = false;
     let (mut input, output) = collect_many(input, |i| {
         let (i, mut wsp) = fws(i).unwrap_or((i, empty_string()));
@@ -135,7 +162,7 @@ pub fn mime_unstructured(input: &[u8]) -> Res<Cow<str>> {
                 add_string(&mut wsp, text);
                 return Ok((i, wsp));
             }
-        } else if let Ok((i, text)) = take_while1(i, is_vchar) {
+        } else if let Ok((i, text)) = take_while1(i, is_vchar::<UTF8>) {
             previous_was_encoded = false;
             add_str(&mut wsp, text);
             return Ok((i, wsp));
@@ -159,37 +186,50 @@ mod tests {
     fn test_encoded_unstructured() {
         assert_eq!(
             "the quick brown fox jumps over Chloé Helloco",
-            mime_unstructured(
+            mime_unstructured::<false>(
                 b"the quick brown fox jumps\r\n over =?UTF-8?Q?Chlo=C3=A9_Helloco?=   "
             )
             .unwrap()
             .1
         );
 
-        assert_eq!("a", mime_unstructured(b"=?ISO-8859-1?Q?a?=").unwrap().1);
-        assert_eq!("a b", mime_unstructured(b"=?ISO-8859-1?Q?a?= b").unwrap().1);
+        assert_eq!(
+            "a",
+            mime_unstructured::<false>(b"=?ISO-8859-1?Q?a?=").unwrap().1
+        );
+        assert_eq!(
+            "a b",
+            mime_unstructured::<false>(b"=?ISO-8859-1?Q?a?= b")
+                .unwrap()
+                .1
+        );
         assert_eq!(
             "ab",
-            mime_unstructured(b"=?ISO-8859-1?Q?a?= =?ISO-8859-1?Q?b?=")
+            mime_unstructured::<false>(b"=?ISO-8859-1?Q?a?= =?ISO-8859-1?Q?b?=")
                 .unwrap()
                 .1
         );
         assert_eq!(
             "ab",
-            mime_unstructured(b"=?ISO-8859-1?Q?a?=  =?ISO-8859-1?Q?b?=")
+            mime_unstructured::<false>(b"=?ISO-8859-1?Q?a?=  =?ISO-8859-1?Q?b?=")
                 .unwrap()
                 .1
         );
         assert_eq!(
             "ab",
-            mime_unstructured(b"=?ISO-8859-1?Q?a?=\r\n  =?ISO-8859-1?Q?b?=")
+            mime_unstructured::<false>(b"=?ISO-8859-1?Q?a?=\r\n  =?ISO-8859-1?Q?b?=")
+                .unwrap()
+                .1
+        );
+        assert_eq!(
+            "a b",
+            mime_unstructured::<false>(b"=?ISO-8859-1?Q?a_b?=")
                 .unwrap()
                 .1
         );
-        assert_eq!("a b", mime_unstructured(b"=?ISO-8859-1?Q?a_b?=").unwrap().1);
         assert_eq!(
             "a b",
-            mime_unstructured(b"=?ISO-8859-1?Q?a?= =?ISO-8859-2?Q?_b?=")
+            mime_unstructured::<false>(b"=?ISO-8859-1?Q?a?= =?ISO-8859-2?Q?_b?=")
                 .unwrap()
                 .1
         );
@@ -199,34 +239,63 @@ mod tests {
     #[test]
     fn test_encoded_phrase() {
         assert_eq!(
-            phrase(b"Lou =?UTF-8?Q?Dorl=C3=A9ans?=").unwrap().1,
+            phrase::<false>(b"Lou =?UTF-8?Q?Dorl=C3=A9ans?=")
+                .unwrap()
+                .1
+                 .0,
             vec!["Lou", "Dorléans"]
         );
 
         assert_eq!(
-            phrase(b"=?ISO-8859-1?Q?Andr=E9?= Pirard").unwrap().1,
+            phrase::<false>(b"=?ISO-8859-1?Q?Andr=E9?= Pirard")
+                .unwrap()
+                .1
+                 .0,
             vec!["André", "Pirard"]
         );
 
         assert_eq!(
-            phrase(b" =?US-ASCII?Q?Keith_Moore?=").unwrap().1,
+            phrase::<false>(b" =?US-ASCII?Q?Keith_Moore?=")
+                .unwrap()
+                .1
+                 .0,
             vec!["Keith Moore"]
         );
+
+        assert_eq!(
+            phrase::<false>(b"=?UTF-8?Q?Andr?=\r\n =?UTF-8?Q?=C3=A9?= =?UTF-8?Q?_Pirard?= Jr")
+                .unwrap()
+                .1
+                 .0,
+            vec!["André Pirard", "Jr"]
+        );
     }
 
     #[test]
     fn test_word_and_phrase() {
-        assert_eq!(word(b" this is a \"rust\\ test\" ").unwrap().1, "this");
         assert_eq!(
-            phrase(b" this is a \"rust\\ test\" ").unwrap().1,
+            word::<false>(b" this is a \"rust\\ test\" ").unwrap().1,
+            "this"
+        );
+        assert_eq!(
+            phrase::<false>(b" this is a \"rust\\ test\" ")
+                .unwrap()
+                .1
+                 .0,
             vec!["this", "is", "a", "rust test"]
         );
+
+        let (input, (words, separators)) =
+            phrase::<false>(b" this  (a comment)is\r\n \"a\"test <").unwrap();
+        assert_eq!(input, b"<");
+        assert_eq!(words, vec!["this", "is", "a", "test"]);
+        assert_eq!(separators, vec!["  ", " ", ""]);
     }
 
     #[test]
     fn test_unstructured() {
         assert_eq!(
-            unstructured(b"the quick brown fox jumps\r\n over the lazy dog   ")
+            unstructured::<false>(b"the quick brown fox jumps\r\n over the lazy dog   ")
                 .unwrap()
                 .1,
             "the quick brown fox jumps over the lazy dog"
@@ -235,12 +304,18 @@ mod tests {
 
     #[test]
     fn test_atom() {
-        assert_eq!(atom(b"this is a test").unwrap().1, "this");
-        assert_eq!(atom(b"   averylongatom ").unwrap().1, "averylongatom");
+        assert_eq!(atom::<false>(b"this is a test").unwrap().1, "this");
+        assert_eq!(
+            atom::<false>(b"   averylongatom ").unwrap().1,
+            "averylongatom"
+        );
+        assert_eq!(
+            dot_atom_text::<false>(b"this.is.a.test").unwrap().1,
+            "this.is.a.test"
+        );
         assert_eq!(
-            dot_atom_text(b"this.is.a.test").unwrap().1,
+            dot_atom::<false>(b"  this.is.a.test ").unwrap().1,
             "this.is.a.test"
         );
-        assert_eq!(dot_atom(b"  this.is.a.test ").unwrap().1, "this.is.a.test");
     }
 }
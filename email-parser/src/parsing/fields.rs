@@ -2,10 +2,8 @@ use crate::address::*;
 use crate::parsing::time::*;
 use crate::prelude::*;
 use std::borrow::Cow;
-#[cfg(feature = "mime")]
-use std::collections::HashMap;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum TraceField<'a> {
     Date(DateTime),
     From(Vec<Mailbox<'a>>),
@@ -19,7 +17,7 @@ pub enum TraceField<'a> {
 #[derive(Debug)]
 pub enum Field<'a> {
     #[cfg(feature = "date")]
-    Date(DateTime),
+    Date(DateTime, DateConfidence),
     #[cfg(feature = "from")]
     From(Vec<Mailbox<'a>>),
     #[cfg(feature = "sender")]
@@ -50,7 +48,7 @@ pub enum Field<'a> {
     ContentType {
         mime_type: ContentType<'a>,
         subtype: Cow<'a, str>,
-        parameters: HashMap<Cow<'a, str>, Cow<'a, str>>,
+        parameters: Parameters<'a>,
     },
     #[cfg(feature = "mime")]
     ContentTransferEncoding(ContentTransferEncoding<'a>),
@@ -72,24 +70,24 @@ pub enum Field<'a> {
     },
 }
 
-pub fn fields(mut input: &[u8]) -> Res<Vec<Field>> {
+pub fn fields<const UTF8: bool>(mut input: &[u8]) -> Res<Vec<Field>> {
     let mut fields: Vec<Field> = Vec::new();
 
     #[cfg(feature = "trace")]
-    while let Ok((new_input, trace)) = trace(input) {
+    while let Ok((new_input, trace)) = trace::<UTF8>(input) {
         input = new_input;
         let mut trace_fields = Vec::new();
 
         while let Ok((new_input, new_result)) = match_parsers(
             input,
             &mut [
-                |i| resent_date(i).map(|(i, v)| (i, TraceField::Date(v))),
-                |i| resent_from(i).map(|(i, v)| (i, TraceField::From(v))),
-                |i| resent_sender(i).map(|(i, v)| (i, TraceField::Sender(v))),
-                |i| resent_to(i).map(|(i, v)| (i, TraceField::To(v))),
-                |i| resent_cc(i).map(|(i, v)| (i, TraceField::Cc(v))),
-                |i| resent_bcc(i).map(|(i, v)| (i, TraceField::Bcc(v))),
-                |i| resent_message_id(i).map(|(i, v)| (i, TraceField::MessageId(v))),
+                |i| resent_date::<UTF8>(i).map(|(i, v)| (i, TraceField::Date(v))),
+                |i| resent_from::<UTF8>(i).map(|(i, v)| (i, TraceField::From(v))),
+                |i| resent_sender::<UTF8>(i).map(|(i, v)| (i, TraceField::Sender(v))),
+                |i| resent_to::<UTF8>(i).map(|(i, v)| (i, TraceField::To(v))),
+                |i| resent_cc::<UTF8>(i).map(|(i, v)| (i, TraceField::Cc(v))),
+                |i| resent_bcc::<UTF8>(i).map(|(i, v)| (i, TraceField::Bcc(v))),
+                |i| resent_message_id::<UTF8>(i).map(|(i, v)| (i, TraceField::MessageId(v))),
             ][..],
         ) {
             input = new_input;
@@ -109,34 +107,34 @@ pub fn fields(mut input: &[u8]) -> Res<Vec<Field>> {
         input,
         &mut [
             #[cfg(feature = "date")]
-            |i| date(i).map(|(i, v)| (i, Field::Date(v))),
+            |i| date::<UTF8>(i).map(|(i, (v, c))| (i, Field::Date(v, c))),
             #[cfg(feature = "from")]
-            |i| from(i).map(|(i, v)| (i, Field::From(v))),
+            |i| from::<UTF8>(i).map(|(i, v)| (i, Field::From(v))),
             #[cfg(feature = "sender")]
-            |i| sender(i).map(|(i, v)| (i, Field::Sender(v))),
+            |i| sender::<UTF8>(i).map(|(i, v)| (i, Field::Sender(v))),
             #[cfg(feature = "reply-to")]
-            |i| reply_to(i).map(|(i, v)| (i, Field::ReplyTo(v))),
+            |i| reply_to::<UTF8>(i).map(|(i, v)| (i, Field::ReplyTo(v))),
             #[cfg(feature = "to")]
-            |i| to(i).map(|(i, v)| (i, Field::To(v))),
+            |i| to::<UTF8>(i).map(|(i, v)| (i, Field::To(v))),
             #[cfg(feature = "cc")]
-            |i| cc(i).map(|(i, v)| (i, Field::Cc(v))),
+            |i| cc::<UTF8>(i).map(|(i, v)| (i, Field::Cc(v))),
             #[cfg(feature = "bcc")]
-            |i| bcc(i).map(|(i, v)| (i, Field::Bcc(v))),
+            |i| bcc::<UTF8>(i).map(|(i, v)| (i, Field::Bcc(v))),
             #[cfg(feature = "message-id")]
-            |i| message_id(i).map(|(i, v)| (i, Field::MessageId(v))),
+            |i| message_id::<UTF8>(i).map(|(i, v)| (i, Field::MessageId(v))),
             #[cfg(feature = "in-reply-to")]
-            |i| in_reply_to(i).map(|(i, v)| (i, Field::InReplyTo(v))),
+            |i| in_reply_to::<UTF8>(i).map(|(i, v)| (i, Field::InReplyTo(v))),
             #[cfg(feature = "references")]
-            |i| references(i).map(|(i, v)| (i, Field::References(v))),
+            |i| references::<UTF8>(i).map(|(i, v)| (i, Field::References(v))),
             #[cfg(feature = "subject")]
-            |i| subject(i).map(|(i, v)| (i, Field::Subject(v))),
+            |i| subject::<UTF8>(i).map(|(i, v)| (i, Field::Subject(v))),
             #[cfg(feature = "comments")]
-            |i| comments(i).map(|(i, v)| (i, Field::Comments(v))),
+            |i| comments::<UTF8>(i).map(|(i, v)| (i, Field::Comments(v))),
             #[cfg(feature = "mime")]
-            |i| mime_version(i).map(|(i, (mj, mn))| (i, Field::MimeVersion(mj, mn))),
+            |i| mime_version::<UTF8>(i).map(|(i, (mj, mn))| (i, Field::MimeVersion(mj, mn))),
             #[cfg(feature = "mime")]
             |i| {
-                content_type(i).map(|(i, (t, st, p))| {
+                content_type::<UTF8>(i).map(|(i, (t, st, p))| {
                     (
                         i,
                         Field::ContentType {
@@ -148,16 +146,19 @@ pub fn fields(mut input: &[u8]) -> Res<Vec<Field>> {
                 })
             },
             #[cfg(feature = "mime")]
-            |i| content_transfer_encoding(i).map(|(i, e)| (i, Field::ContentTransferEncoding(e))),
+            |i| {
+                content_transfer_encoding::<UTF8>(i)
+                    .map(|(i, e)| (i, Field::ContentTransferEncoding(e)))
+            },
             #[cfg(feature = "mime")]
-            |i| content_id(i).map(|(i, v)| (i, Field::ContentId(v))),
+            |i| content_id::<UTF8>(i).map(|(i, v)| (i, Field::ContentId(v))),
             #[cfg(feature = "mime")]
-            |i| content_description(i).map(|(i, d)| (i, Field::ContentDescription(d))),
+            |i| content_description::<UTF8>(i).map(|(i, d)| (i, Field::ContentDescription(d))),
             #[cfg(feature = "content-disposition")]
-            |i| content_disposition(i).map(|(i, d)| (i, Field::ContentDisposition(d))),
+            |i| content_disposition::<UTF8>(i).map(|(i, d)| (i, Field::ContentDisposition(d))),
             #[cfg(feature = "keywords")]
-            |i| keywords(i).map(|(i, v)| (i, Field::Keywords(v))),
-            |i| unknown(i).map(|(i, (name, value))| (i, Field::Unknown { name, value })),
+            |i| keywords::<UTF8>(i).map(|(i, v)| (i, Field::Keywords(v))),
+            |i| unknown::<UTF8>(i).map(|(i, (name, value))| (i, Field::Unknown { name, value })),
         ][..],
     ) {
         input = new_input;
@@ -167,31 +168,41 @@ pub fn fields(mut input: &[u8]) -> Res<Vec<Field>> {
     Ok((input, fields))
 }
 
-pub fn date(input: &[u8]) -> Res<DateTime> {
+pub fn date<const UTF8: bool>(input: &[u8]) -> Res<(DateTime, DateConfidence)> {
     let (input, ()) = tag_no_case(
         input,
         b"Date:",
         b"dATE:",
         "TAG NO CASE ERROR: Header name (Date) does not match.",
     )?;
-    let (input, date_time) = date_time(input)?;
+    if let Ok((input, date_time)) = date_time::<UTF8>(input) {
+        if let Ok((input, ())) = tag(input, b"\r\n", "") {
+            if let Some(confidence) = strict_confidence(&date_time) {
+                return Ok((input, (date_time, confidence)));
+            }
+        }
+    }
+
+    // Dates produced by broken mailers should not make the whole email invalid.
+    let (input, value) = unstructured::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
         "TAG ERROR: A header (`Date` in this case) must end with a CRLF sequence.",
     )?;
+    let date_time = lenient_date_time::<UTF8>(&value).ok_or(Error::Unknown("Invalid date"))?;
 
     Ok((input, date_time))
 }
 
-pub fn from(input: &[u8]) -> Res<Vec<Mailbox>> {
+pub fn from<const UTF8: bool>(input: &[u8]) -> Res<Vec<Mailbox>> {
     let (input, ()) = tag_no_case(
         input,
         b"From:",
         b"fROM:",
         "TAG NO CASE ERROR: Header name (From) does not match.",
     )?;
-    let (input, mailbox_list) = mailbox_list(input)?;
+    let (input, mailbox_list) = mailbox_list::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -201,14 +212,14 @@ pub fn from(input: &[u8]) -> Res<Vec<Mailbox>> {
     Ok((input, mailbox_list))
 }
 
-pub fn sender(input: &[u8]) -> Res<Mailbox> {
+pub fn sender<const UTF8: bool>(input: &[u8]) -> Res<Mailbox> {
     let (input, ()) = tag_no_case(
         input,
         b"Sender:",
         b"sENDER:",
         "TAG NO CASE ERROR: Header name (Sender) does not match.",
     )?;
-    let (input, mailbox) = mailbox(input)?;
+    let (input, mailbox) = mailbox::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -218,14 +229,14 @@ pub fn sender(input: &[u8]) -> Res<Mailbox> {
     Ok((input, mailbox))
 }
 
-pub fn reply_to(input: &[u8]) -> Res<Vec<Address>> {
+pub fn reply_to<const UTF8: bool>(input: &[u8]) -> Res<Vec<Address>> {
     let (input, ()) = tag_no_case(
         input,
         b"Reply-To:",
         b"rEPLY-tO:",
         "TAG NO CASE ERROR: Header name (Reply-To) does not match.",
     )?;
-    let (input, mailbox) = address_list(input)?;
+    let (input, mailbox) = address_list::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -235,14 +246,14 @@ pub fn reply_to(input: &[u8]) -> Res<Vec<Address>> {
     Ok((input, mailbox))
 }
 
-pub fn to(input: &[u8]) -> Res<Vec<Address>> {
+pub fn to<const UTF8: bool>(input: &[u8]) -> Res<Vec<Address>> {
     let (input, ()) = tag_no_case(
         input,
         b"To:",
         b"tO:",
         "TAG NO CASE ERROR: Header name (To) does not match.",
     )?;
-    let (input, mailbox) = address_list(input)?;
+    let (input, mailbox) = address_list::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -252,14 +263,14 @@ pub fn to(input: &[u8]) -> Res<Vec<Address>> {
     Ok((input, mailbox))
 }
 
-pub fn cc(input: &[u8]) -> Res<Vec<Address>> {
+pub fn cc<const UTF8: bool>(input: &[u8]) -> Res<Vec<Address>> {
     let (input, ()) = tag_no_case(
         input,
         b"Cc:",
         b"cC:",
         "TAG NO CASE ERROR: Header name (Cc) does not match.",
     )?;
-    let (input, mailbox) = address_list(input)?;
+    let (input, mailbox) = address_list::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -269,16 +280,16 @@ pub fn cc(input: &[u8]) -> Res<Vec<Address>> {
     Ok((input, mailbox))
 }
 
-pub fn bcc(input: &[u8]) -> Res<Vec<Address>> {
+pub fn bcc<const UTF8: bool>(input: &[u8]) -> Res<Vec<Address>> {
     let (input, ()) = tag_no_case(
         input,
         b"Bcc:",
         b"bCC:",
         "TAG NO CASE ERROR: Header name (Bcc) does not match.",
     )?;
-    let (input, mailbox) = if let Ok((input, list)) = address_list(input) {
+    let (input, mailbox) = if let Ok((input, list)) = address_list::<UTF8>(input) {
         (input, list)
-    } else if let Ok((input, _cfws)) = cfws(input) {
+    } else if let Ok((input, _cfws)) = cfws::<UTF8>(input) {
         (input, Vec::new())
     } else {
         return Err(Error::Unknown("Invalid bcc field"));
@@ -292,14 +303,14 @@ pub fn bcc(input: &[u8]) -> Res<Vec<Address>> {
     Ok((input, mailbox))
 }
 
-pub fn message_id(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
+pub fn message_id<const UTF8: bool>(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
     let (input, ()) = tag_no_case(
         input,
         b"Message-ID:",
         b"mESSAGE-id:",
         "TAG NO CASE ERROR: Header name (Message-ID) does not match.",
     )?;
-    let (input, id) = crate::parsing::address::message_id(input)?;
+    let (input, id) = crate::parsing::address::message_id::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -309,14 +320,14 @@ pub fn message_id(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
     Ok((input, id))
 }
 
-pub fn in_reply_to(input: &[u8]) -> Res<Vec<(Cow<str>, Cow<str>)>> {
+pub fn in_reply_to<const UTF8: bool>(input: &[u8]) -> Res<Vec<(Cow<str>, Cow<str>)>> {
     let (input, ()) = tag_no_case(
         input,
         b"In-Reply-To:",
         b"iN-rEPLY-tO:",
         "TAG NO CASE ERROR: Header name (Reply-To) does not match.",
     )?;
-    let (input, ids) = many1(input, crate::parsing::address::message_id)?;
+    let (input, ids) = many1(input, crate::parsing::address::message_id::<UTF8>)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -326,14 +337,14 @@ pub fn in_reply_to(input: &[u8]) -> Res<Vec<(Cow<str>, Cow<str>)>> {
     Ok((input, ids))
 }
 
-pub fn references(input: &[u8]) -> Res<Vec<(Cow<str>, Cow<str>)>> {
+pub fn references<const UTF8: bool>(input: &[u8]) -> Res<Vec<(Cow<str>, Cow<str>)>> {
     let (input, ()) = tag_no_case(
         input,
         b"References:",
         b"rEFERENCES:",
         "TAG NO CASE ERROR: Header name (References) does not match.",
     )?;
-    let (input, ids) = many1(input, crate::parsing::address::message_id)?;
+    let (input, ids) = many1(input, crate::parsing::address::message_id::<UTF8>)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -343,7 +354,7 @@ pub fn references(input: &[u8]) -> Res<Vec<(Cow<str>, Cow<str>)>> {
     Ok((input, ids))
 }
 
-pub fn subject(input: &[u8]) -> Res<Cow<str>> {
+pub fn subject<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
     let (input, ()) = tag_no_case(
         input,
         b"Subject:",
@@ -351,9 +362,9 @@ pub fn subject(input: &[u8]) -> Res<Cow<str>> {
         "TAG NO CASE ERROR: Header name (Subject) does not match.",
     )?;
     #[cfg(not(feature = "mime"))]
-    let (input, subject) = unstructured(input)?;
+    let (input, subject) = unstructured::<UTF8>(input)?;
     #[cfg(feature = "mime")]
-    let (input, subject) = mime_unstructured(input)?;
+    let (input, subject) = mime_unstructured::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -363,7 +374,7 @@ pub fn subject(input: &[u8]) -> Res<Cow<str>> {
     Ok((input, subject))
 }
 
-pub fn comments(input: &[u8]) -> Res<Cow<str>> {
+pub fn comments<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
     let (input, ()) = tag_no_case(
         input,
         b"Comments:",
@@ -371,9 +382,9 @@ pub fn comments(input: &[u8]) -> Res<Cow<str>> {
         "TAG NO CASE ERROR: Header name (Comments) does not match.",
     )?;
     #[cfg(not(feature = "mime"))]
-    let (input, comments) = unstructured(input)?;
+    let (input, comments) = unstructured::<UTF8>(input)?;
     #[cfg(feature = "mime")]
-    let (input, comments) = mime_unstructured(input)?;
+    let (input, comments) = mime_unstructured::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -383,7 +394,7 @@ pub fn comments(input: &[u8]) -> Res<Cow<str>> {
     Ok((input, comments))
 }
 
-pub fn keywords(input: &[u8]) -> Res<Vec<Vec<Cow<str>>>> {
+pub fn keywords<const UTF8: bool>(input: &[u8]) -> Res<Vec<Vec<Cow<str>>>> {
     let (input, ()) = tag_no_case(
         input,
         b"Keywords:",
@@ -392,10 +403,10 @@ pub fn keywords(input: &[u8]) -> Res<Vec<Vec<Cow<str>>>> {
     )?;
 
     let mut keywords = Vec::new();
-    let (mut input, first_keyword) = phrase(input)?;
+    let (mut input, (first_keyword, _separators)) = phrase::<UTF8>(input)?;
     keywords.push(first_keyword);
 
-    while let Ok((new_input, new_keyword)) = prefixed(input, phrase, ",") {
+    while let Ok((new_input, (new_keyword, _separators))) = prefixed(input, phrase::<UTF8>, ",") {
         input = new_input;
         keywords.push(new_keyword);
     }
@@ -409,97 +420,97 @@ pub fn keywords(input: &[u8]) -> Res<Vec<Vec<Cow<str>>>> {
     Ok((input, keywords))
 }
 
-pub fn resent_date(input: &[u8]) -> Res<DateTime> {
+pub fn resent_date<const UTF8: bool>(input: &[u8]) -> Res<DateTime> {
     let (input, ()) = tag_no_case(
         input,
         b"Resent-",
         b"rESENT-",
         "TAG NO CASE ERROR: Header name (Resent-Date) does not match.",
     )?;
-    let (input, date) = date(input)?;
+    let (input, (date, _confidence)) = date::<UTF8>(input)?;
 
     Ok((input, date))
 }
 
-pub fn resent_from(input: &[u8]) -> Res<Vec<Mailbox>> {
+pub fn resent_from<const UTF8: bool>(input: &[u8]) -> Res<Vec<Mailbox>> {
     let (input, ()) = tag_no_case(
         input,
         b"Resent-",
         b"rESENT-",
         "TAG NO CASE ERROR: Header name (Resent-From) does not match.",
     )?;
-    let (input, from) = from(input)?;
+    let (input, from) = from::<UTF8>(input)?;
 
     Ok((input, from))
 }
 
-pub fn resent_sender(input: &[u8]) -> Res<Mailbox> {
+pub fn resent_sender<const UTF8: bool>(input: &[u8]) -> Res<Mailbox> {
     let (input, ()) = tag_no_case(
         input,
         b"Resent-",
         b"rESENT-",
         "TAG NO CASE ERROR: Header name (Resent-Sender) does not match.",
     )?;
-    let (input, sender) = sender(input)?;
+    let (input, sender) = sender::<UTF8>(input)?;
 
     Ok((input, sender))
 }
 
-pub fn resent_to(input: &[u8]) -> Res<Vec<Address>> {
+pub fn resent_to<const UTF8: bool>(input: &[u8]) -> Res<Vec<Address>> {
     let (input, ()) = tag_no_case(
         input,
         b"Resent-",
         b"rESENT-",
         "TAG NO CASE ERROR: Header name (Resent-To) does not match.",
     )?;
-    let (input, to) = to(input)?;
+    let (input, to) = to::<UTF8>(input)?;
 
     Ok((input, to))
 }
 
-pub fn resent_cc(input: &[u8]) -> Res<Vec<Address>> {
+pub fn resent_cc<const UTF8: bool>(input: &[u8]) -> Res<Vec<Address>> {
     let (input, ()) = tag_no_case(
         input,
         b"Resent-",
         b"rESENT-",
         "TAG NO CASE ERROR: Header name (Resent-Cc) does not match.",
     )?;
-    let (input, cc) = cc(input)?;
+    let (input, cc) = cc::<UTF8>(input)?;
 
     Ok((input, cc))
 }
 
-pub fn resent_bcc(input: &[u8]) -> Res<Vec<Address>> {
+pub fn resent_bcc<const UTF8: bool>(input: &[u8]) -> Res<Vec<Address>> {
     let (input, ()) = tag_no_case(
         input,
         b"Resent-",
         b"rESENT-",
         "TAG NO CASE ERROR: Header name (Resent-Bcc) does not match.",
     )?;
-    let (input, bcc) = bcc(input)?;
+    let (input, bcc) = bcc::<UTF8>(input)?;
 
     Ok((input, bcc))
 }
 
-pub fn resent_message_id(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
+pub fn resent_message_id<const UTF8: bool>(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
     let (input, ()) = tag_no_case(
         input,
         b"Resent-",
         b"rESENT-",
         "TAG NO CASE ERROR: Header name (Resent-Message-ID) does not match.",
     )?;
-    let (input, id) = message_id(input)?;
+    let (input, id) = message_id::<UTF8>(input)?;
 
     Ok((input, id))
 }
 
-pub fn return_path(input: &[u8]) -> Res<Option<EmailAddress>> {
-    fn empty_path(input: &[u8]) -> Res<()> {
-        let (input, _cfws) = optional(input, cfws);
+pub fn return_path<const UTF8: bool>(input: &[u8]) -> Res<Option<EmailAddress>> {
+    fn empty_path<const UTF8: bool>(input: &[u8]) -> Res<()> {
+        let (input, _cfws) = optional(input, cfws::<UTF8>);
         let (input, ()) = tag(input, b"<", "TAG ERROR: An empty path must start with `<`.")?;
-        let (input, _cfws) = optional(input, cfws);
+        let (input, _cfws) = optional(input, cfws::<UTF8>);
         let (input, ()) = tag(input, b">", "TAG ERROR: An empty path must end with `>`.")?;
-        let (input, _cfws) = optional(input, cfws);
+        let (input, _cfws) = optional(input, cfws::<UTF8>);
         Ok((input, ()))
     }
 
@@ -512,9 +523,9 @@ pub fn return_path(input: &[u8]) -> Res<Option<EmailAddress>> {
     let (input, addr) = match_parsers(
         input,
         &mut [
-            (|i| angle_addr(i).map(|(i, v)| (i, Some(v))))
+            (|i| angle_addr::<UTF8>(i).map(|(i, v)| (i, Some(v))))
                 as fn(input: &[u8]) -> Res<Option<EmailAddress>>,
-            (|i| empty_path(i).map(|(i, _)| (i, None)))
+            (|i| empty_path::<UTF8>(i).map(|(i, _)| (i, None)))
                 as fn(input: &[u8]) -> Res<Option<EmailAddress>>,
         ][..],
     )?;
@@ -527,44 +538,74 @@ pub fn return_path(input: &[u8]) -> Res<Option<EmailAddress>> {
     Ok((input, addr))
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ReceivedToken<'a> {
     Word(Cow<'a, str>),
     Addr(EmailAddress<'a>),
     Domain(Cow<'a, str>),
+    /// Comments often contain the IP addresses of the hosts.
+    Comment(Cow<'a, str>),
+}
+
+fn received_token<const UTF8: bool>(input: &[u8]) -> Res<'_, ReceivedToken<'_>> {
+    if let Ok((word_input, word)) = word::<UTF8>(input) {
+        if let Ok((domain_input, domain)) = domain::<UTF8>(input) {
+            if domain.len() > word.len() {
+                return Ok((domain_input, ReceivedToken::Domain(domain)));
+            }
+        }
+        Ok((word_input, ReceivedToken::Word(word)))
+    } else if let Ok((input, addr)) = angle_addr::<UTF8>(input) {
+        Ok((input, ReceivedToken::Addr(addr)))
+    } else if let Ok((input, addr)) = addr_spec::<UTF8>(input) {
+        Ok((input, ReceivedToken::Addr(addr)))
+    } else if let Ok((input, domain)) = domain::<UTF8>(input) {
+        Ok((input, ReceivedToken::Domain(domain)))
+    } else {
+        Err(Error::Unknown("match error"))
+    }
 }
 
-pub fn received(input: &[u8]) -> Res<(Vec<ReceivedToken>, DateTime)> {
+pub fn received<const UTF8: bool>(input: &[u8]) -> Res<(Vec<ReceivedToken>, DateTime)> {
     let (input, ()) = tag_no_case(
         input,
         b"Received:",
         b"rECEIVED:",
         "TAG NO CASE ERROR: Header name (Received) does not match.",
     )?;
-    let (input, received_tokens) = many(input, |input| {
-        if let Ok((word_input, word)) = word(input) {
-            if let Ok((domain_input, domain)) = domain(input) {
-                if domain.len() > word.len() {
-                    return Ok((domain_input, ReceivedToken::Domain(domain)));
-                }
+    let mut input = input;
+    let mut received_tokens = Vec::new();
+    loop {
+        // Tokens consume the comments following them, so only the first comments need to be parsed here.
+        if let Ok((new_input, _cfws)) = cfws::<UTF8>(input) {
+            let consumed = &input[..input.len() - new_input.len()];
+            received_tokens.extend(
+                crate::parsing::whitespaces::comments::<UTF8>(consumed)
+                    .into_iter()
+                    .map(ReceivedToken::Comment),
+            );
+            input = new_input;
+        }
+        match received_token::<UTF8>(input) {
+            Ok((new_input, token)) => {
+                let consumed = &input[..input.len() - new_input.len()];
+                received_tokens.push(token);
+                received_tokens.extend(
+                    crate::parsing::whitespaces::comments::<UTF8>(consumed)
+                        .into_iter()
+                        .map(ReceivedToken::Comment),
+                );
+                input = new_input;
             }
-            Ok((word_input, ReceivedToken::Word(word)))
-        } else if let Ok((input, addr)) = angle_addr(input) {
-            Ok((input, ReceivedToken::Addr(addr)))
-        } else if let Ok((input, addr)) = addr_spec(input) {
-            Ok((input, ReceivedToken::Addr(addr)))
-        } else if let Ok((input, domain)) = domain(input) {
-            Ok((input, ReceivedToken::Domain(domain)))
-        } else {
-            Err(Error::Unknown("match error"))
+            Err(_) => break,
         }
-    })?;
+    }
     let (input, ()) = tag(
         input,
         b";",
         "TAG ERROR: Received tokens must be followed by a `;`.",
     )?;
-    let (input, date_time) = date_time(input)?;
+    let (input, date_time) = date_time::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -574,19 +615,19 @@ pub fn received(input: &[u8]) -> Res<(Vec<ReceivedToken>, DateTime)> {
     Ok((input, (received_tokens, date_time)))
 }
 
-pub fn trace(
+pub fn trace<const UTF8: bool>(
     input: &[u8],
 ) -> Res<(
     Option<Option<EmailAddress>>,
     Vec<(Vec<ReceivedToken>, DateTime)>,
 )> {
-    let (input, return_path) = optional(input, return_path);
-    let (input, received) = many1(input, received)?;
+    let (input, return_path) = optional(input, return_path::<UTF8>);
+    let (input, received) = many1(input, received::<UTF8>)?;
 
     Ok((input, (return_path, received)))
 }
 
-pub fn unknown(input: &[u8]) -> Res<(&str, Cow<str>)> {
+pub fn unknown<const UTF8: bool>(input: &[u8]) -> Res<(&str, Cow<str>)> {
     let (input, name) = take_while1(input, is_ftext)?;
     let (input, ()) = tag(
         input,
@@ -594,9 +635,9 @@ pub fn unknown(input: &[u8]) -> Res<(&str, Cow<str>)> {
         "TAG ERROR: A header name must be followed by a `:`.",
     )?;
     #[cfg(not(feature = "unrecognized-headers"))]
-    let (input, value) = unstructured(input)?;
+    let (input, value) = unstructured::<UTF8>(input)?;
     #[cfg(feature = "unrecognized-headers")]
-    let (input, value) = mime_unstructured(input)?;
+    let (input, value) = mime_unstructured::<UTF8>(input)?;
 
     let (input, ()) = tag(
         input,
@@ -607,13 +648,41 @@ pub fn unknown(input: &[u8]) -> Res<(&str, Cow<str>)> {
     Ok((input, (name, value)))
 }
 
+/// Splits a raw header section into fields.\
+/// Each field is returned as a `(name, field)` tuple where `field` contains the whole folded field, including its final CRLF.
+pub fn header_fields(mut input: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut fields = Vec::new();
+
+    while !input.is_empty() {
+        let mut end = input.len();
+        let mut idx = 0;
+        while idx + 1 < input.len() {
+            if input[idx] == b'\r'
+                && input[idx + 1] == b'\n'
+                && !matches!(input.get(idx + 2), Some(b' ') | Some(b'\t'))
+            {
+                end = idx + 2;
+                break;
+            }
+            idx += 1;
+        }
+
+        let field = &input[..end];
+        let name_len = field.iter().position(|c| *c == b':').unwrap_or(0);
+        fields.push((&field[..name_len], field));
+        input = &input[end..];
+    }
+
+    fields
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_fields() {
-        assert!(fields(
+        assert!(fields::<false>(
             b"To: Mubelotix <mubelotix@gmail.com>\r\nFrOm: Mubelotix <mubelotix@gmail.com>\r\n"
         )
         .unwrap()
@@ -622,23 +691,58 @@ mod tests {
         //println!("{:#?}", fields(include_bytes!("../../mail.txt")).unwrap().1);
     }
 
+    #[test]
+    fn test_header_fields() {
+        assert_eq!(
+            header_fields(b"From: a@b.c\r\nSubject: Folded\r\n subject\r\nTo: d@e.f\r\n"),
+            vec![
+                (&b"From"[..], &b"From: a@b.c\r\n"[..]),
+                (&b"Subject"[..], &b"Subject: Folded\r\n subject\r\n"[..]),
+                (&b"To"[..], &b"To: d@e.f\r\n"[..]),
+            ]
+        );
+        assert!(header_fields(b"").is_empty());
+    }
+
     #[test]
     fn test_unknown_field() {
         assert_eq!(
-            unknown(b"hidden-field:hidden message\r\n").unwrap().1 .1,
+            unknown::<false>(b"hidden-field:hidden message\r\n")
+                .unwrap()
+                .1
+                 .1,
             "hidden message"
         );
         assert_eq!(
-            unknown(b"hidden-field:hidden message\r\n").unwrap().1 .0,
+            unknown::<false>(b"hidden-field:hidden message\r\n")
+                .unwrap()
+                .1
+                 .0,
             "hidden-field"
         );
     }
 
     #[test]
     fn test_trace() {
-        assert!(return_path(b"Return-Path:<>\r\n").unwrap().1.is_none());
+        let (tokens, _date) = received::<false>(b"Received: (qmail 1234) from mail.example.com (mail.example.com [192.0.2.1])\r\n by mx.example.net; 5 May 2003 18:59:03 +0000\r\n").unwrap().1;
+        assert_eq!(
+            tokens,
+            vec![
+                ReceivedToken::Comment("qmail 1234".into()),
+                ReceivedToken::Word("from".into()),
+                ReceivedToken::Domain("mail.example.com".into()),
+                ReceivedToken::Comment("mail.example.com [192.0.2.1]".into()),
+                ReceivedToken::Word("by".into()),
+                ReceivedToken::Domain("mx.example.net".into()),
+            ]
+        );
+
+        assert!(return_path::<false>(b"Return-Path:<>\r\n")
+            .unwrap()
+            .1
+            .is_none());
         assert_eq!(
-            return_path(b"Return-Path:<mubelotix@gmail.com>\r\n")
+            return_path::<false>(b"Return-Path:<mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1
                 .unwrap()
@@ -647,35 +751,35 @@ mod tests {
         );
 
         assert!(matches!(
-            received(b"Received:test<mubelotix@gmail.com>;5 May 2003 18:59:03 +0000\r\n")
+            received::<false>(b"Received:test<mubelotix@gmail.com>;5 May 2003 18:59:03 +0000\r\n")
                 .unwrap()
                 .1
                  .0[0],
             ReceivedToken::Word(_)
         ));
         assert!(matches!(
-            received(b"Received:test<mubelotix@gmail.com>;5 May 2003 18:59:03 +0000\r\n")
+            received::<false>(b"Received:test<mubelotix@gmail.com>;5 May 2003 18:59:03 +0000\r\n")
                 .unwrap()
                 .1
                  .0[1],
             ReceivedToken::Addr(_)
         ));
         assert!(matches!(
-            received(b"Received:mubelotix.dev;5 May 2003 18:59:03 +0000\r\n")
+            received::<false>(b"Received:mubelotix.dev;5 May 2003 18:59:03 +0000\r\n")
                 .unwrap()
                 .1
                  .0[0],
             ReceivedToken::Domain(_)
         ));
 
-        assert!(trace(b"Return-Path:<>\r\nReceived:akala miam miam;5 May 2003 18:59:03 +0000\r\nReceived:mubelotix.dev;5 May 2003 18:59:03 +0000\r\n").unwrap().0.is_empty());
+        assert!(trace::<false>(b"Return-Path:<>\r\nReceived:akala miam miam;5 May 2003 18:59:03 +0000\r\nReceived:mubelotix.dev;5 May 2003 18:59:03 +0000\r\n").unwrap().0.is_empty());
     }
 
     #[test]
     fn test_resent() {
-        assert!(resent_date(b"Resent-Date:5 May 2003 18:59:03 +0000\r\n").is_ok());
+        assert!(resent_date::<false>(b"Resent-Date:5 May 2003 18:59:03 +0000\r\n").is_ok());
         assert_eq!(
-            resent_from(b"Resent-FrOm: Mubelotix <mubelotix@gmail.com>\r\n")
+            resent_from::<false>(b"Resent-FrOm: Mubelotix <mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1[0]
                 .address
@@ -683,7 +787,7 @@ mod tests {
             "mubelotix"
         );
         assert_eq!(
-            resent_sender(b"Resent-sender: Mubelotix <mubelotix@gmail.com>\r\n")
+            resent_sender::<false>(b"Resent-sender: Mubelotix <mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1
                 .address
@@ -691,19 +795,19 @@ mod tests {
             "gmail.com"
         );
         assert!(
-            !resent_to(b"Resent-To: Mubelotix <mubelotix@gmail.com>\r\n")
+            !resent_to::<false>(b"Resent-To: Mubelotix <mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1
                 .is_empty()
         );
         assert!(
-            !resent_cc(b"Resent-Cc: Mubelotix <mubelotix@gmail.com>\r\n")
+            !resent_cc::<false>(b"Resent-Cc: Mubelotix <mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1
                 .is_empty()
         );
         assert!(
-            !resent_bcc(b"Resent-Bcc: Mubelotix <mubelotix@gmail.com>\r\n")
+            !resent_bcc::<false>(b"Resent-Bcc: Mubelotix <mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1
                 .is_empty()
@@ -712,13 +816,20 @@ mod tests {
 
     #[test]
     fn test_date() {
-        assert!(date(b"Date:5 May 2003 18:59:03 +0000\r\n").is_ok());
+        assert!(date::<false>(b"Date:5 May 2003 18:59:03 +0000\r\n").is_ok());
+        assert!(date::<false>(b"Date: 1 Jan 99999999999999999 00:00 +0000\r\n").is_err());
+
+        let (_input, (date_time, confidence)) =
+            date::<false>(b"Date: Tue, 5 May 2003 18:59:03 +0000\r\n").unwrap();
+        assert_eq!(date_time.day_name, Some(Day::Tuesday));
+        assert_eq!(date_time.time.time.second, 3);
+        assert_eq!(confidence, DateConfidence::Guessed);
     }
 
     #[test]
     fn test_originators() {
         assert_eq!(
-            from(b"FrOm: Mubelotix <mubelotix@gmail.com>\r\n")
+            from::<false>(b"FrOm: Mubelotix <mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1[0]
                 .address
@@ -726,7 +837,7 @@ mod tests {
             "mubelotix"
         );
         assert_eq!(
-            sender(b"sender: Mubelotix <mubelotix@gmail.com>\r\n")
+            sender::<false>(b"sender: Mubelotix <mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1
                 .address
@@ -734,7 +845,7 @@ mod tests {
             "gmail.com"
         );
         assert_eq!(
-            reply_to(b"Reply-to: Mubelotix <mubelotix@gmail.com>\r\n")
+            reply_to::<false>(b"Reply-to: Mubelotix <mubelotix@gmail.com>\r\n")
                 .unwrap()
                 .1
                 .len(),
@@ -744,32 +855,32 @@ mod tests {
 
     #[test]
     fn test_destination() {
-        assert!(!to(b"To: Mubelotix <mubelotix@gmail.com>\r\n")
+        assert!(!to::<false>(b"To: Mubelotix <mubelotix@gmail.com>\r\n")
             .unwrap()
             .1
             .is_empty());
-        assert!(!cc(b"Cc: Mubelotix <mubelotix@gmail.com>\r\n")
+        assert!(!cc::<false>(b"Cc: Mubelotix <mubelotix@gmail.com>\r\n")
             .unwrap()
             .1
             .is_empty());
-        assert!(!bcc(b"Bcc: Mubelotix <mubelotix@gmail.com>\r\n")
+        assert!(!bcc::<false>(b"Bcc: Mubelotix <mubelotix@gmail.com>\r\n")
             .unwrap()
             .1
             .is_empty());
-        assert!(bcc(b"Bcc: \r\n \r\n").unwrap().1.is_empty());
+        assert!(bcc::<false>(b"Bcc: \r\n \r\n").unwrap().1.is_empty());
     }
 
     #[test]
     fn test_ids() {
         assert_eq!(
-            message_id(b"Message-ID:<556100154@gmail.com>\r\n")
+            message_id::<false>(b"Message-ID:<556100154@gmail.com>\r\n")
                 .unwrap()
                 .1
                  .0,
             "556100154"
         );
         assert_eq!(
-            message_id(b"Message-ID:<556100154@gmail.com>\r\n")
+            message_id::<false>(b"Message-ID:<556100154@gmail.com>\r\n")
                 .unwrap()
                 .1
                  .1,
@@ -777,7 +888,7 @@ mod tests {
         );
 
         assert_eq!(
-            references(b"References:<qzdzdq@qdz.com><dzdzjd@zdzdj.dz>\r\n")
+            references::<false>(b"References:<qzdzdq@qdz.com><dzdzjd@zdzdj.dz>\r\n")
                 .unwrap()
                 .1
                 .len(),
@@ -785,7 +896,7 @@ mod tests {
         );
 
         assert_eq!(
-            in_reply_to(b"In-Reply-To:<eefes@qzd.fr><52@s.dz><adzd@zd.d>\r\n")
+            in_reply_to::<false>(b"In-Reply-To:<eefes@qzd.fr><52@s.dz><adzd@zd.d>\r\n")
                 .unwrap()
                 .1
                 .len(),
@@ -796,21 +907,25 @@ mod tests {
     #[test]
     fn test_informational() {
         assert_eq!(
-            subject(b"Subject:French school is boring\r\n").unwrap().1,
+            subject::<false>(b"Subject:French school is boring\r\n")
+                .unwrap()
+                .1,
             "French school is boring"
         );
         assert_eq!(
-            subject(b"Subject:Folding\r\n is slow\r\n").unwrap().1,
+            subject::<false>(b"Subject:Folding\r\n is slow\r\n")
+                .unwrap()
+                .1,
             "Folding is slow"
         );
 
         assert_eq!(
-            comments(b"Comments:Rust is great\r\n").unwrap().1,
+            comments::<false>(b"Comments:Rust is great\r\n").unwrap().1,
             "Rust is great"
         );
 
         assert_eq!(
-            keywords(b"Keywords:rust parser fast zero copy,email rfc5322\r\n")
+            keywords::<false>(b"Keywords:rust parser fast zero copy,email rfc5322\r\n")
                 .unwrap()
                 .1
                 .len(),
@@ -822,17 +937,17 @@ mod tests {
     #[cfg(all(feature = "mime", feature = "unrecognized-headers"))]
     fn test_mime_encoding() {
         assert_eq!(
-            subject(b"Subject: =?UTF-8?B?8J+OiEJpcnRoZGF5IEdpdmVhd2F58J+OiA==?= Win free stickers\r\n from daily.dev =?UTF-8?B?8J+MiA==?=\r\n").unwrap().1,
+            subject::<false>(b"Subject: =?UTF-8?B?8J+OiEJpcnRoZGF5IEdpdmVhd2F58J+OiA==?= Win free stickers\r\n from daily.dev =?UTF-8?B?8J+MiA==?=\r\n").unwrap().1,
             " 🎈Birthday Giveaway🎈 Win free stickers from daily.dev 🌈"
         );
 
         assert_eq!(
-            comments(b"Comments: =?ISO-8859-1?B?SWYgeW91IGNhbiByZWFkIHRoaXMgeW8=?=\r\n =?ISO-8859-2?B?dSB1bmRlcnN0YW5kIHRoZSBleGFtcGxlLg==?=\r\n").unwrap().1,
+            comments::<false>(b"Comments: =?ISO-8859-1?B?SWYgeW91IGNhbiByZWFkIHRoaXMgeW8=?=\r\n =?ISO-8859-2?B?dSB1bmRlcnN0YW5kIHRoZSBleGFtcGxlLg==?=\r\n").unwrap().1,
             " If you can read this you understand the example."
         );
 
         assert_eq!(
-            from(b"From: =?US-ASCII?Q?Keith_Moore?= <moore@cs.utk.edu>\r\n")
+            from::<false>(b"From: =?US-ASCII?Q?Keith_Moore?= <moore@cs.utk.edu>\r\n")
                 .unwrap()
                 .1[0]
                 .name
@@ -842,7 +957,7 @@ mod tests {
         );
 
         assert_eq!(
-            unknown(b"X-SG-EID:\r\n =?us-ascii?Q?t3vk5cTFE=2FYEGeQ8h3SwrnzIAGc=2F+ADymlys=2FfRFW4Zjpt=2F3MuaO9JNHS2enYQ?=\r\n =?us-ascii?Q?Jsv0=2FpYrPem+YssHetKlrE5nJnOfr=2FYdJOyJFf8?=\r\n =?us-ascii?Q?3mRuMRE9KGu=2F5O75=2FwwN6dG14nuP4SyMIZwbMdG?=\r\n =?us-ascii?Q?vXmM2kgcM=2FOalKeT03BMp4YCg9h1LhkV6PZEoHB?=\r\n =?us-ascii?Q?d4tcAvNZQqLaA4ykI1EpNxKVVyZXVWqTp2uisdf?=\r\n =?us-ascii?Q?HB=2F6BKcIs+XSDNeakQqmn=2FwAqOk78AvtRB5LnNL?=\r\n =?us-ascii?Q?lz3oRXlMZbdFgRH+KAyLQ=3D=3D?=\r\n").unwrap().1.1,
+            unknown::<false>(b"X-SG-EID:\r\n =?us-ascii?Q?t3vk5cTFE=2FYEGeQ8h3SwrnzIAGc=2F+ADymlys=2FfRFW4Zjpt=2F3MuaO9JNHS2enYQ?=\r\n =?us-ascii?Q?Jsv0=2FpYrPem+YssHetKlrE5nJnOfr=2FYdJOyJFf8?=\r\n =?us-ascii?Q?3mRuMRE9KGu=2F5O75=2FwwN6dG14nuP4SyMIZwbMdG?=\r\n =?us-ascii?Q?vXmM2kgcM=2FOalKeT03BMp4YCg9h1LhkV6PZEoHB?=\r\n =?us-ascii?Q?d4tcAvNZQqLaA4ykI1EpNxKVVyZXVWqTp2uisdf?=\r\n =?us-ascii?Q?HB=2F6BKcIs+XSDNeakQqmn=2FwAqOk78AvtRB5LnNL?=\r\n =?us-ascii?Q?lz3oRXlMZbdFgRH+KAyLQ=3D=3D?=\r\n").unwrap().1.1,
             " t3vk5cTFE/YEGeQ8h3SwrnzIAGc/+ADymlys/fRFW4Zjpt/3MuaO9JNHS2enYQJsv0/pYrPem+YssHetKlrE5nJnOfr/YdJOyJFf83mRuMRE9KGu/5O75/wwN6dG14nuP4SyMIZwbMdGvXmM2kgcM/OalKeT03BMp4YCg9h1LhkV6PZEoHBd4tcAvNZQqLaA4ykI1EpNxKVVyZXVWqTp2uisdfHB/6BKcIs+XSDNeakQqmn/wAqOk78AvtRB5LnNLlz3oRXlMZbdFgRH+KAyLQ=="
         );
     }
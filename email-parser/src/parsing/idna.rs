@@ -0,0 +1,391 @@
+use crate::address::AddressError;
+use crate::prelude::*;
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// The prefix of the labels encoded with punycode.\
+/// [Learn more](https://tools.ietf.org/html/rfc5890#section-2.3.2.1)
+const ACE_PREFIX: &str = "xn--";
+
+/// The maximum length of a domain name, without its trailing dot.\
+/// [Learn more](https://tools.ietf.org/html/rfc1035#section-2.3.4)
+const MAX_DOMAIN_LEN: usize = 253;
+const MAX_LABEL_LEN: usize = 63;
+
+/// [Learn more](https://tools.ietf.org/html/rfc3492#section-6.1)
+fn adapt(mut delta: u32, points: u32, first_time: bool) -> u32 {
+    delta /= if first_time { DAMP } else { 2 };
+    delta += delta / points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn encode_digit(digit: u32) -> char {
+    match digit {
+        0..=25 => (b'a' + digit as u8) as char,
+        _ => (b'0' + (digit - 26) as u8) as char,
+    }
+}
+
+fn decode_digit(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32),
+        'A'..='Z' => Some(c as u32 - 'A' as u32),
+        '0'..='9' => Some(c as u32 - '0' as u32 + 26),
+        _ => None,
+    }
+}
+
+/// Encodes a string with punycode, without the `xn--` prefix.\
+/// [Learn more](https://tools.ietf.org/html/rfc3492#section-6.3)
+pub fn punycode_encode(input: &str) -> Result<String, Error> {
+    let input: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let mut output: String = input
+        .iter()
+        .filter(|c| **c < 0x80)
+        .map(|c| *c as u8 as char)
+        .collect();
+    let basic_len = output.len() as u32;
+    if basic_len > 0 {
+        output.push('-');
+    }
+
+    let overflow = || Error::Unknown("Punycode overflow");
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut handled = basic_len;
+    while (handled as usize) < input.len() {
+        let m = input.iter().copied().filter(|c| *c >= n).min().unwrap_or(n);
+        delta = (m - n)
+            .checked_mul(handled + 1)
+            .and_then(|d| delta.checked_add(d))
+            .ok_or_else(overflow)?;
+        n = m;
+
+        for c in input.iter().copied() {
+            if c < n {
+                delta = delta.checked_add(1).ok_or_else(overflow)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic_len);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1).ok_or_else(overflow)?;
+        n += 1;
+    }
+
+    Ok(output)
+}
+
+/// Decodes a punycode string, given without the `xn--` prefix.\
+/// [Learn more](https://tools.ietf.org/html/rfc3492#section-6.2)
+pub fn punycode_decode(input: &str) -> Result<String, Error> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(idx) => (&input[..idx], &input[idx + 1..]),
+        None => ("", input),
+    };
+    if !basic.is_ascii() {
+        return Err(Error::Unknown("Punycode basic code points must be ASCII"));
+    }
+
+    let overflow = || Error::Unknown("Punycode overflow");
+    let mut output: Vec<char> = basic.chars().collect();
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut digits = extended.chars().peekable();
+    while digits.peek().is_some() {
+        let old_i = i;
+        let mut weight: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = digits
+                .next()
+                .and_then(decode_digit)
+                .ok_or(Error::Unknown("Invalid punycode digit"))?;
+            i = digit
+                .checked_mul(weight)
+                .and_then(|d| i.checked_add(d))
+                .ok_or_else(overflow)?;
+            let t = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+            weight = weight.checked_mul(BASE - t).ok_or_else(overflow)?;
+            k += BASE;
+        }
+
+        let len = output.len() as u32 + 1;
+        bias = adapt(i - old_i, len, old_i == 0);
+        n = n.checked_add(i / len).ok_or_else(overflow)?;
+        i %= len;
+        let c = char::from_u32(n).ok_or(Error::Unknown("Invalid punycode code point"))?;
+        output.insert(i as usize, c);
+        i += 1;
+    }
+
+    Ok(output.into_iter().collect())
+}
+
+/// Maps a domain before its conversion: ideographic full stops become dots and letters are lowercased.\
+/// [Learn more](https://www.unicode.org/reports/tr46/#Processing)
+fn map(domain: &str) -> String {
+    domain
+        .chars()
+        .map(|c| match c {
+            '\u{3002}' | '\u{FF0E}' | '\u{FF61}' => '.',
+            c => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Splits a domain into its labels, ignoring the trailing dot of fully qualified domains.
+fn labels(domain: &str) -> Result<std::str::Split<'_, char>, Error> {
+    let domain = domain.strip_suffix('.').unwrap_or(domain);
+    if domain.is_empty() || domain.split('.').any(str::is_empty) {
+        return Err(Error::Unknown("Domain labels cannot be empty"));
+    }
+    Ok(domain.split('.'))
+}
+
+/// Decodes an A-label, checking that it is the canonical encoding of a non-ASCII label.
+fn decode_a_label(label: &str) -> Result<String, Error> {
+    let decoded = punycode_decode(&label[ACE_PREFIX.len()..])?;
+    if decoded.is_ascii() || punycode_encode(&decoded)? != label[ACE_PREFIX.len()..] {
+        return Err(Error::Unknown("Invalid A-label"));
+    }
+    Ok(decoded)
+}
+
+fn is_a_label(label: &str) -> bool {
+    label.len() > ACE_PREFIX.len()
+        && label.as_bytes()[..ACE_PREFIX.len()].eq_ignore_ascii_case(ACE_PREFIX.as_bytes())
+}
+
+/// The UTS #46 processing used with the `idna` feature, rejecting the code points disallowed by IDNA2008.\
+/// ASCII rules are left to [validate], so that conversions accept any host name.
+#[cfg(feature = "idna")]
+fn uts46() -> ::idna::Config {
+    ::idna::Config::default().use_idna_2008_rules(true)
+}
+
+/// Checks the labels of a domain before its conversion by the `idna` crate, which is more permissive with empty labels and A-labels.
+#[cfg(feature = "idna")]
+fn check_labels(domain: &str) -> Result<(), Error> {
+    for label in labels(&map(domain))? {
+        if is_a_label(label) {
+            decode_a_label(label)?;
+        }
+    }
+    Ok(())
+}
+
+/// Converts a domain to its ASCII form, encoding Unicode labels as A-labels.\
+/// The domain is normalized to NFC and disallowed code points are rejected.\
+/// [Learn more](https://www.unicode.org/reports/tr46/#ToASCII)
+#[cfg(feature = "idna")]
+pub fn to_ascii(domain: &str) -> Result<String, Error> {
+    check_labels(domain)?;
+    uts46()
+        .to_ascii(domain)
+        .map_err(|_| Error::Unknown("Invalid internationalized domain name"))
+}
+
+/// Converts a domain to its Unicode form, decoding A-labels.\
+/// The domain is normalized to NFC and disallowed code points are rejected.\
+/// [Learn more](https://www.unicode.org/reports/tr46/#ToUnicode)
+#[cfg(feature = "idna")]
+pub fn to_unicode(domain: &str) -> Result<String, Error> {
+    check_labels(domain)?;
+    let (output, result) = uts46().to_unicode(domain);
+    result.map_err(|_| Error::Unknown("Invalid internationalized domain name"))?;
+    Ok(output)
+}
+
+/// Converts a domain to its ASCII form, encoding Unicode labels as A-labels.\
+/// Unicode normalization and the checks of disallowed code points require the `idna` feature.\
+/// [Learn more](https://www.unicode.org/reports/tr46/#ToASCII)
+#[cfg(not(feature = "idna"))]
+pub fn to_ascii(domain: &str) -> Result<String, Error> {
+    punycode_to_ascii(domain)
+}
+
+/// Converts a domain to its ASCII form with punycode only: letters are lowercased but the domain is not normalized.\
+/// The result does not depend on the `idna` feature, which makes it suitable to compare domains.
+pub fn punycode_to_ascii(domain: &str) -> Result<String, Error> {
+    let mapped = map(domain);
+    let mut output = String::with_capacity(mapped.len());
+    for label in labels(&mapped)? {
+        if !output.is_empty() {
+            output.push('.');
+        }
+        if label.is_ascii() {
+            if is_a_label(label) {
+                decode_a_label(label)?;
+            }
+            output.push_str(label);
+        } else {
+            output.push_str(ACE_PREFIX);
+            output.push_str(&punycode_encode(label)?);
+        }
+    }
+    if mapped.ends_with('.') {
+        output.push('.');
+    }
+    Ok(output)
+}
+
+/// Converts a domain to its Unicode form, decoding A-labels.\
+/// Unicode normalization and the checks of disallowed code points require the `idna` feature.\
+/// [Learn more](https://www.unicode.org/reports/tr46/#ToUnicode)
+#[cfg(not(feature = "idna"))]
+pub fn to_unicode(domain: &str) -> Result<String, Error> {
+    let mapped = map(domain);
+    let mut output = String::with_capacity(mapped.len());
+    for label in labels(&mapped)? {
+        if !output.is_empty() {
+            output.push('.');
+        }
+        if is_a_label(label) {
+            output.push_str(&decode_a_label(label)?);
+        } else {
+            output.push_str(label);
+        }
+    }
+    if mapped.ends_with('.') {
+        output.push('.');
+    }
+    Ok(output)
+}
+
+/// Checks that a domain is a valid host name once converted to ASCII: labels are made of letters, digits and hyphens and length limits are respected.\
+/// [Learn more](https://tools.ietf.org/html/rfc5891#section-5.4)
+pub fn validate(domain: &str) -> Result<(), AddressError> {
+    labels(&map(domain)).map_err(|_| AddressError::EmptyDomainLabel)?;
+    let ascii = to_ascii(domain).map_err(|_| AddressError::InvalidIdn)?;
+    let ascii = ascii.strip_suffix('.').unwrap_or(&ascii);
+    if ascii.len() > MAX_DOMAIN_LEN {
+        return Err(AddressError::DomainTooLong);
+    }
+
+    for label in ascii.split('.') {
+        if label.len() > MAX_LABEL_LEN {
+            return Err(AddressError::DomainLabelTooLong);
+        }
+        if !label
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'-')
+        {
+            return Err(AddressError::InvalidDomainCharacter);
+        }
+        if label.starts_with('-')
+            || label.ends_with('-')
+            || (label.get(2..4) == Some("--") && !is_a_label(label))
+        {
+            return Err(AddressError::InvalidDomainHyphen);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_punycode() {
+        let samples = [
+            ("bücher", "bcher-kva"),
+            ("münchen", "mnchen-3ya"),
+            ("他们为什么不说中文", "ihqwcrb4cv8a8dqg056pqjye"),
+            ("3年B組金八先生", "3B-ww4c5e180e575a65lsy2b"),
+            ("ليهمابتكلموشعربي؟", "egbpdaj6bu4bxfgehfvwxn"),
+        ];
+        for (decoded, encoded) in samples {
+            assert_eq!(punycode_encode(decoded).unwrap(), encoded);
+            assert_eq!(punycode_decode(encoded).unwrap(), decoded);
+        }
+        assert!(punycode_decode("bcher-kv!").is_err());
+        assert!(punycode_decode("99999999999").is_err());
+    }
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(to_ascii("Bücher.DE").unwrap(), "xn--bcher-kva.de");
+        assert_eq!(to_ascii("bücher。de.").unwrap(), "xn--bcher-kva.de.");
+        assert_eq!(to_ascii("XN--BCHER-KVA.de").unwrap(), "xn--bcher-kva.de");
+        assert_eq!(to_unicode("xn--bcher-kva.DE").unwrap(), "bücher.de");
+        assert_eq!(to_unicode("bücher.de").unwrap(), "bücher.de");
+        assert_eq!(to_unicode("उदाहरण.भारत").unwrap(), "उदाहरण.भारत");
+
+        assert!(to_ascii("xn--example-.de").is_err());
+        assert!(to_ascii("example..com").is_err());
+        assert!(to_unicode("").is_err());
+
+        assert_eq!(punycode_to_ascii("Bücher.DE").unwrap(), "xn--bcher-kva.de");
+        assert_eq!(
+            punycode_to_ascii("bu\u{308}cher.de").unwrap(),
+            "xn--bucher-xyd.de"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("mubelotix.dev").is_ok());
+        assert!(validate("exämple.de").is_ok());
+        assert!(validate("-example.com").is_err());
+        assert!(validate("exa--mple.com").is_ok());
+        assert!(validate("ab--cd.com").is_err());
+        assert!(validate("under_score.com").is_err());
+        assert!(validate(&format!("{}.com", "a".repeat(64))).is_err());
+        assert!(validate(&["a"; 128].join(".")).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "idna")]
+    fn test_uts46() {
+        assert_eq!(to_ascii("bu\u{308}cher.de").unwrap(), "xn--bcher-kva.de");
+        assert_eq!(to_unicode("bu\u{308}cher.de").unwrap(), "bücher.de");
+        assert_eq!(validate("bu\u{308}cher.de"), validate("bücher.de"));
+        assert_eq!(validate("☃.com"), Err(AddressError::InvalidIdn));
+        assert_eq!(validate("💩.la"), Err(AddressError::InvalidIdn));
+        assert!(validate("उदाहरण.भारत").is_ok());
+    }
+}
@@ -109,7 +109,15 @@ pub fn body(input: &[u8]) -> Result<Option<Cow<str>>, Error> {
 
 #[cfg(not(feature = "mime"))]
 pub fn parse_message(input: &[u8]) -> Result<(Vec<Field>, Option<Cow<str>>), Error> {
-    let (input, fields) = fields(input)?;
+    parse_message_with::<UTF8_HEADERS>(input)
+}
+
+/// Like [parse_message], but `UTF8` tells whether header fields can contain UTF-8.
+#[cfg(not(feature = "mime"))]
+pub(crate) fn parse_message_with<const UTF8: bool>(
+    input: &[u8],
+) -> Result<(Vec<Field<'_>>, Option<Cow<'_, str>>), Error> {
+    let (input, fields) = fields::<UTF8>(input)?;
     let body = body(input)?;
 
     Ok((fields, body))
@@ -117,7 +125,15 @@ pub fn parse_message(input: &[u8]) -> Result<(Vec<Field>, Option<Cow<str>>), Err
 
 #[cfg(feature = "mime")]
 pub fn parse_message(input: &[u8]) -> Result<(Vec<Field>, Option<&[u8]>), Error> {
-    let (input, fields) = fields(input)?;
+    parse_message_with::<UTF8_HEADERS>(input)
+}
+
+/// Like [parse_message], but `UTF8` tells whether header fields can contain UTF-8.
+#[cfg(feature = "mime")]
+pub(crate) fn parse_message_with<const UTF8: bool>(
+    input: &[u8],
+) -> Result<(Vec<Field<'_>>, Option<&[u8]>), Error> {
+    let (input, fields) = fields::<UTF8>(input)?;
 
     if input.is_empty() {
         return Ok((fields, None));
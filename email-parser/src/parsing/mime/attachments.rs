@@ -0,0 +1,185 @@
+use crate::prelude::*;
+use std::borrow::Cow;
+
+/// Decodes encoded words in a parameter value.\
+/// This is forbidden by RFC 2047 but many mailers use them in file names anyway.
+pub fn decode_encoded_words(value: Cow<str>) -> Cow<str> {
+    if value.contains("=?") {
+        if let Ok((b"", decoded)) = mime_unstructured::<true>(value.as_bytes()) {
+            return Cow::Owned(decoded.into_owned());
+        }
+    }
+    value
+}
+
+/// Device names that Windows reserves in every directory, whatever the extension.
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Removes path separators, characters forbidden on Windows and control characters from a file name so that it can safely be used as a path component.\
+/// Leading dots, trailing dots and surrounding whitespaces are removed too, and reserved device names such as `CON` are prefixed with `_`.
+/// Returns `None` if nothing is left.
+pub fn sanitize_filename(filename: Cow<str>) -> Option<Cow<str>> {
+    let is_forbidden = |c: char| "/\\<>:\"|?*".contains(c) || c.is_control();
+
+    let filename = if filename.contains(is_forbidden) {
+        Cow::Owned(filename.replace(is_forbidden, "_"))
+    } else {
+        filename
+    };
+
+    let trimmed = filename
+        .trim_start_matches(|c: char| c == '.' || c.is_whitespace())
+        .trim_end_matches(|c: char| c == '.' || c.is_whitespace());
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let stem = trimmed.split('.').next().unwrap_or(trimmed).trim_end();
+    if RESERVED_NAMES
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(stem))
+    {
+        Some(Cow::Owned(format!("_{}", trimmed)))
+    } else if trimmed.len() == filename.len() {
+        Some(filename)
+    } else {
+        Some(Cow::Owned(trimmed.to_string()))
+    }
+}
+
+/// Returns the attachment represented by a non-multipart entity, or `None` if this entity is a part of the body.\
+/// Entities are considered to be attachments if they are explicitly marked as such, if they have a file name, or if they are not text.
+pub fn attachment(entity: RawEntity) -> Option<Attachment> {
+    let RawEntity {
+        mime_type,
+        subtype,
+        id,
+        mut parameters,
+        #[cfg(feature = "content-disposition")]
+        disposition,
+        value,
+        ..
+    } = entity;
+
+    #[cfg(feature = "content-disposition")]
+    let (disposition, filename) = match disposition {
+        Some(mut disposition) => {
+            let filename = match disposition.filename {
+                Some(filename) => Some(filename),
+                None => disposition.unstructured.remove("filename"),
+            };
+            (Some(disposition.disposition_type), filename)
+        }
+        None => (None, None),
+    };
+    #[cfg(not(feature = "content-disposition"))]
+    let filename = None;
+
+    let filename = filename
+        .or_else(|| parameters.remove("name"))
+        .map(decode_encoded_words)
+        .and_then(sanitize_filename);
+
+    #[cfg(feature = "content-disposition")]
+    let is_attachment = match disposition {
+        Some(DispositionType::Attachment) | Some(DispositionType::Unknown(_)) => true,
+        Some(DispositionType::Inline) | None => {
+            filename.is_some() || mime_type != ContentType::Text
+        }
+    };
+    #[cfg(not(feature = "content-disposition"))]
+    let is_attachment = filename.is_some() || mime_type != ContentType::Text;
+
+    if !is_attachment {
+        return None;
+    }
+
+    Some(Attachment {
+        filename,
+        content_type: mime_type,
+        subtype,
+        size: value.len(),
+        content_id: id,
+        #[cfg(feature = "content-disposition")]
+        disposition,
+        data: value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(
+            sanitize_filename("report.pdf".into()).unwrap(),
+            "report.pdf"
+        );
+        assert_eq!(
+            sanitize_filename("../../etc/passwd".into()).unwrap(),
+            "_.._etc_passwd"
+        );
+        assert_eq!(
+            sanitize_filename("C:\\Users\\me\\photo.jpg".into()).unwrap(),
+            "C__Users_me_photo.jpg"
+        );
+        assert_eq!(
+            sanitize_filename("a.txt:stream".into()).unwrap(),
+            "a.txt_stream"
+        );
+        assert_eq!(
+            sanitize_filename("<why?> \"really\"|*".into()).unwrap(),
+            "_why__ _really___"
+        );
+        assert_eq!(sanitize_filename(" .hidden\0 ".into()).unwrap(), "hidden_");
+        assert_eq!(sanitize_filename("report. . ".into()).unwrap(), "report");
+        assert!(sanitize_filename(" .. ".into()).is_none());
+
+        assert_eq!(sanitize_filename("CON".into()).unwrap(), "_CON");
+        assert_eq!(sanitize_filename("nul.txt".into()).unwrap(), "_nul.txt");
+        assert_eq!(
+            sanitize_filename("Com1 .tar.gz".into()).unwrap(),
+            "_Com1 .tar.gz"
+        );
+        assert_eq!(
+            sanitize_filename("console.log".into()).unwrap(),
+            "console.log"
+        );
+    }
+
+    #[test]
+    fn test_decode_encoded_words() {
+        assert_eq!(
+            decode_encoded_words("=?UTF-8?Q?r=C3=A9sum=C3=A9.pdf?=".into()),
+            "résumé.pdf"
+        );
+        assert_eq!(decode_encoded_words("plain.txt".into()), "plain.txt");
+    }
+
+    #[test]
+    fn test_attachments() {
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: multipart/mixed; boundary=outer\r\n\r\n--outer\r\nContent-Type: multipart/alternative; boundary=inner\r\n\r\n--inner\r\nContent-Type: text/plain\r\n\r\nHello\r\n--inner\r\nContent-Type: text/html\r\n\r\n<p>Hello</p>\r\n--inner--\r\n\r\n--outer\r\nContent-Type: image/png; name=\"=?UTF-8?Q?=C3=A9t=C3=A9.png?=\"\r\nContent-ID: <image@mubelotix.dev>\r\nContent-Transfer-Encoding: base64\r\n\r\naGVsbG8=\r\n--outer\r\nContent-Type: text/plain\r\nContent-Disposition: attachment; filename*=utf-8''..%2Fnotes.txt\r\n\r\nSome notes\r\n--outer--\r\n").unwrap();
+
+        let attachments: Vec<Attachment> = email.attachments().collect();
+        assert_eq!(attachments.len(), 2);
+
+        assert_eq!(attachments[0].filename.as_ref().unwrap(), "été.png");
+        assert_eq!(attachments[0].content_type, ContentType::Image);
+        assert_eq!(attachments[0].subtype, "png");
+        assert_eq!(attachments[0].size, 5);
+        assert_eq!(attachments[0].data, Cow::Borrowed(b"hello"));
+        assert_eq!(attachments[0].content_id.as_ref().unwrap().0, "image");
+
+        assert_eq!(attachments[1].filename.as_ref().unwrap(), "_notes.txt");
+        assert_eq!(attachments[1].data, Cow::Borrowed(b"Some notes"));
+        #[cfg(feature = "content-disposition")]
+        assert_eq!(
+            attachments[1].disposition,
+            Some(DispositionType::Attachment)
+        );
+    }
+}
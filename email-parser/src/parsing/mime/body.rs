@@ -0,0 +1,126 @@
+use crate::prelude::*;
+use std::borrow::Cow;
+
+/// Returns `false` if an entity is explicitly marked as an attachment.
+fn is_inline(entity: &RawEntity) -> bool {
+    #[cfg(feature = "content-disposition")]
+    {
+        !matches!(
+            entity.disposition.as_ref().map(|d| &d.disposition_type),
+            Some(DispositionType::Attachment) | Some(DispositionType::Unknown(_))
+        )
+    }
+    #[cfg(not(feature = "content-disposition"))]
+    {
+        let _ = entity;
+        true
+    }
+}
+
+/// Returns the Content-ID of an entity in the `<left@right>` form.
+pub fn content_id(entity: &RawEntity) -> Option<String> {
+    entity
+        .id
+        .as_ref()
+        .map(|(left, right)| format!("<{}@{}>", left, right))
+}
+
+/// Looks for the displayable text part of the given subtype.\
+/// In a `multipart/alternative` entity, the last matching alternative is preferred.
+/// In a `multipart/related` entity, only the root part (see the `start` parameter) is considered.
+/// In any other multipart entity, the first matching part is selected.\
+/// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.1)
+pub fn find_text<'a>(entity: RawEntity<'a>, subtype: &str) -> Option<RawEntity<'a>> {
+    if !is_inline(&entity) {
+        return None;
+    }
+
+    match entity.mime_type {
+        ContentType::Text if entity.subtype == subtype => Some(entity),
+        ContentType::Multipart => {
+            let multipart_subtype = entity.subtype.to_string();
+            let start = entity
+                .parameters
+                .get("start")
+                .map(|start| start.to_string());
+            let root_type = entity
+                .parameters
+                .get("type")
+                .map(|root_type| root_type.to_string());
+            let parts = crate::parsing::mime::multipart::parts(entity).ok()?;
+
+            match multipart_subtype.as_str() {
+                "alternative" => parts
+                    .into_iter()
+                    .rev()
+                    .find_map(|part| find_text(part, subtype)),
+                "related" => {
+                    let root =
+                        super::related::root_index(&parts, start.as_deref(), root_type.as_deref());
+                    find_text(parts.into_iter().nth(root)?, subtype)
+                }
+                _ => parts.into_iter().find_map(|part| find_text(part, subtype)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Decodes a text entity, assuming US-ASCII when no charset is specified.
+pub fn decode_body(entity: RawEntity) -> Option<Cow<str>> {
+    let charset = entity
+        .parameters
+        .get("charset")
+        .map(|charset| charset.to_string())
+        .unwrap_or_else(|| String::from("us-ascii"));
+    super::entity::decode_text(entity.value, &charset)
+        .ok()
+        .flatten()
+}
+
+pub fn text_body(entity: RawEntity) -> Option<Cow<str>> {
+    if let Some(plain) = find_text(entity.clone(), "plain") {
+        let flowed = Flowed::from_entity(&plain);
+        if let Some(text) = decode_body(plain) {
+            return Some(match flowed {
+                Some(flowed) => Cow::Owned(flowed.decode(&text)),
+                None => text,
+            });
+        }
+    }
+
+    let html = find_text(entity, "html").and_then(decode_body)?;
+    Some(Cow::Owned(super::html::html_to_text(&html)))
+}
+
+pub fn html_body(entity: RawEntity) -> Option<Cow<str>> {
+    find_text(entity, "html").and_then(decode_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_selection() {
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: multipart/mixed; boundary=mixed\r\n\r\n--mixed\r\nContent-Type: multipart/related; boundary=related; start=\"<root@mubelotix.dev>\"\r\n\r\n--related\r\nContent-Type: text/plain\r\n\r\nNot the root\r\n--related\r\nContent-Type: multipart/alternative; boundary=alternative\r\nContent-ID: <root@mubelotix.dev>\r\n\r\n--alternative\r\nContent-Type: text/plain; charset=utf-8\r\n\r\nPlain text\r\n--alternative\r\nContent-Type: text/html; charset=iso-8859-1\r\nContent-Transfer-Encoding: quoted-printable\r\n\r\n<p>HTML t=E9xt</p>\r\n--alternative--\r\n\r\n--related--\r\n\r\n--mixed\r\nContent-Type: text/plain\r\nContent-Disposition: attachment\r\n\r\nAttached text\r\n--mixed--\r\n").unwrap();
+        assert_eq!(email.text_body().unwrap(), "Plain text");
+        assert_eq!(email.html_body().unwrap(), "<p>HTML téxt</p>");
+
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: multipart/alternative; boundary=alternative\r\n\r\n--alternative\r\nContent-Type: text/html\r\n\r\n<p>First</p>\r\n--alternative\r\nContent-Type: text/html\r\n\r\n<p>Last &amp; best</p>\r\n--alternative--\r\n").unwrap();
+        assert_eq!(email.html_body().unwrap(), "<p>Last &amp; best</p>");
+        assert_eq!(email.text_body().unwrap(), "Last & best");
+
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\n\r\nHey!\r\n").unwrap();
+        assert_eq!(email.text_body().unwrap(), "Hey!\r\n");
+        assert!(email.html_body().is_none());
+
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: text/plain; format=flowed; delsp=yes\r\n\r\nA long  \r\nline.\r\n").unwrap();
+        assert_eq!(email.text_body().unwrap(), "A long line.\r\n");
+        assert!(email.html_body().is_none());
+
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: multipart/related; boundary=related; start=\"<missing@mubelotix.dev>\"; type=\"text/html\"\r\n\r\n--related\r\nContent-Type: text/plain\r\n\r\nNot the root\r\n--related\r\nContent-Type: text/html\r\n\r\n<p>Root</p>\r\n--related--\r\n").unwrap();
+        assert_eq!(email.html_body().unwrap(), "<p>Root</p>");
+        assert_eq!(email.text_body().unwrap(), "Root");
+    }
+}
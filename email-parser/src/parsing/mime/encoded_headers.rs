@@ -43,14 +43,15 @@ fn encoded_text(input: &[u8]) -> Res<&str> {
     take_while1(input, |c| c > 0x20 && c <= 0x7E && c != b'?')
 }
 
-pub fn encoded_word(input: &[u8]) -> Res<Cow<str>> {
+/// Parses an encoded word, returning its decoded text, its charset and its language.
+fn encoded_word_parts(input: &[u8]) -> Res<'_, (Cow<'_, str>, Cow<'_, str>, Option<Cow<'_, str>>)> {
     let (input, _) = tag(
         input,
         b"=?",
         "TAG ERROR: An encoded word must start with `=?`.",
     )?;
     let (input, charset) = charset(input)?;
-    let (input, _language) = match optional(input, |input| {
+    let (input, language) = match optional(input, |input| {
         pair(
             input,
             |input| {
@@ -111,7 +112,193 @@ pub fn encoded_word(input: &[u8]) -> Res<Cow<str>> {
         _ => return Err(Error::Unknown("Unknown charset")),
     };
 
-    Ok((input, text))
+    Ok((input, (text, charset, language)))
+}
+
+pub fn encoded_word(input: &[u8]) -> Res<Cow<str>> {
+    encoded_word_parts(input).map(|(input, (text, _charset, _language))| (input, text))
+}
+
+/// Appends text to the last segment if it has the same charset and language, or creates a new segment.
+fn push_segment<'a>(
+    segments: &mut Vec<HeaderSegment<'a>>,
+    text: Cow<'a, str>,
+    charset: Option<Cow<'a, str>>,
+    language: Option<Cow<'a, str>>,
+) {
+    match segments.last_mut() {
+        Some(last) if last.charset == charset && last.language == language => {
+            add_string(&mut last.text, text)
+        }
+        _ => segments.push(HeaderSegment {
+            text,
+            charset,
+            language,
+        }),
+    }
+}
+
+/// Splits an unstructured header value into segments of plain text and decoded encoded words.\
+/// Whitespaces between adjacent encoded words are ignored, and adjacent segments with the same charset and language are merged.
+/// Text that cannot be parsed is kept as a plain segment.\
+/// [Learn more](https://tools.ietf.org/html/rfc2047#section-6.2)
+pub fn header_segments(mut input: &[u8]) -> Vec<HeaderSegment<'_>> {
+    let mut segments = Vec::new();
+    let mut previous_was_encoded = false;
+
+    loop {
+        let (i, wsp) = fws(input).unwrap_or((input, empty_string()));
+
+        if let Ok((i, (text, charset, language))) = encoded_word_parts(i) {
+            if !previous_was_encoded && !wsp.is_empty() {
+                push_segment(&mut segments, wsp, None, None);
+            }
+            push_segment(&mut segments, text, Some(charset), language);
+            previous_was_encoded = true;
+            input = i;
+        } else if let Ok((i, text)) = take_while1(i, is_vchar::<UTF8_HEADERS>) {
+            if !wsp.is_empty() {
+                push_segment(&mut segments, wsp, None, None);
+            }
+            push_segment(&mut segments, Cow::Borrowed(text), None, None);
+            previous_was_encoded = false;
+            input = i;
+        } else {
+            if !i.is_empty() {
+                push_segment(&mut segments, String::from_utf8_lossy(input), None, None);
+            }
+            break;
+        }
+    }
+
+    segments
+}
+
+/// The maximum length of the encoded text of a UTF-8 encoded word.\
+/// [Learn more](https://tools.ietf.org/html/rfc2047#section-2)
+const MAX_ENCODED_TEXT_LEN: usize = 75 - "=?utf-8?q??=".len();
+
+/// Returns `true` if the character can be written as is in the Q encoding.\
+/// [Learn more](https://tools.ietf.org/html/rfc2047#section-5)
+fn is_q_literal(c: u8, context: &EncodedWordContext) -> bool {
+    match context {
+        EncodedWordContext::Phrase => {
+            c.is_ascii_alphanumeric() || matches!(c, b'!' | b'*' | b'+' | b'-' | b'/')
+        }
+        EncodedWordContext::Comment => {
+            c > 0x20 && c < 0x7F && !matches!(c, b'=' | b'?' | b'_' | b'(' | b')' | b'"' | b'\\')
+        }
+        EncodedWordContext::Unstructured => {
+            c > 0x20 && c < 0x7F && !matches!(c, b'=' | b'?' | b'_')
+        }
+    }
+}
+
+/// Returns `true` if a word can be written without being encoded.
+fn is_plain_word(word: &str, context: &EncodedWordContext) -> bool {
+    word.is_ascii()
+        && !word.contains("=?")
+        && word.bytes().all(|c| match context {
+            EncodedWordContext::Phrase => is_atext::<false>(c),
+            EncodedWordContext::Comment => is_ctext::<false>(c),
+            EncodedWordContext::Unstructured => is_vchar::<false>(c),
+        })
+}
+
+fn q_encode(text: &str, context: &EncodedWordContext) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for c in text.bytes() {
+        if c == b' ' {
+            encoded.push('_');
+        } else if is_q_literal(c, context) {
+            encoded.push(c as char);
+        } else {
+            encoded.push_str(&format!("={:02X}", c));
+        }
+    }
+    encoded
+}
+
+fn b_encode(text: &str) -> String {
+    let mut encoded = base64::encode_base64(text.as_bytes().to_vec());
+    encoded.retain(|c| *c != b'\r' && *c != b'\n');
+
+    // JUSTIFICATION
+    //  Benefit
+    //      Avoid checking the output of the base64 encoder.
+    //  Correctness
+    //      Base64 is valid ASCII so it cannot be invalid utf8.
+    unsafe { String::from_utf8_unchecked(encoded) }
+}
+
+/// Encodes text as a list of encoded words separated by spaces.\
+/// The text is split between characters so that each encoded word fits in 75 characters.
+fn encode_words(text: &str, q: bool, context: &EncodedWordContext) -> String {
+    let encoded_len = |chunk: &str| {
+        if q {
+            q_encode(chunk, context).len()
+        } else {
+            chunk.len().div_ceil(3) * 4
+        }
+    };
+
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (idx, c) in text.char_indices() {
+        if idx > start && encoded_len(&text[start..idx + c.len_utf8()]) > MAX_ENCODED_TEXT_LEN {
+            words.push(&text[start..idx]);
+            start = idx;
+        }
+    }
+    words.push(&text[start..]);
+
+    words
+        .into_iter()
+        .map(|word| match q {
+            true => format!("=?utf-8?q?{}?=", q_encode(word, context)),
+            false => format!("=?utf-8?b?{}?=", b_encode(word)),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Encodes the words that cannot be written as is, choosing the shortest of the B and Q encodings.\
+/// Consecutive words are encoded together, with the spaces separating them.\
+/// [Learn more](https://tools.ietf.org/html/rfc2047#section-5)
+pub fn encode(text: &str, context: &EncodedWordContext) -> String {
+    let words: Vec<&str> = text.split(' ').collect();
+    let mut output = String::with_capacity(text.len());
+    let mut idx = 0;
+
+    while idx < words.len() {
+        if idx > 0 {
+            output.push(' ');
+        }
+
+        if words[idx].is_empty() || is_plain_word(words[idx], context) {
+            output.push_str(words[idx]);
+            idx += 1;
+            continue;
+        }
+
+        // Spaces between encoded words would be dropped when decoding, so they are kept in the run
+        let mut end = idx + 1;
+        let mut scan = end;
+        while scan < words.len() && (words[scan].is_empty() || !is_plain_word(words[scan], context))
+        {
+            scan += 1;
+            if !words[scan - 1].is_empty() {
+                end = scan;
+            }
+        }
+        let run = words[idx..end].join(" ");
+        let q = encode_words(&run, true, context);
+        let b = encode_words(&run, false, context);
+        output.push_str(if q.len() <= b.len() { &q } else { &b });
+        idx = end;
+    }
+
+    output
 }
 
 #[cfg(test)]
@@ -134,6 +321,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encode_test() {
+        let context = EncodedWordContext::Unstructured;
+        assert_eq!(encode("Hello world", &context), "Hello world");
+        assert_eq!(
+            encode("Développement durable", &context),
+            "=?utf-8?q?D=C3=A9veloppement?= durable"
+        );
+        assert_eq!(
+            encode("Café au lait", &context),
+            "=?utf-8?b?Q2Fmw6k=?= au lait"
+        );
+        assert_eq!(
+            encode("Hello ☃☃☃☃", &context),
+            "Hello =?utf-8?b?4piD4piD4piD4piD?="
+        );
+        assert_eq!(encode("a =?b?= c", &context), "a =?utf-8?b?PT9iPz0=?= c");
+        assert_eq!(
+            encode("Doe, Jöhn", &EncodedWordContext::Phrase),
+            "=?utf-8?q?Doe=2C_J=C3=B6hn?="
+        );
+        assert_eq!(
+            encode("(Développement durable)", &EncodedWordContext::Comment),
+            "=?utf-8?q?=28D=C3=A9veloppement_durable=29?="
+        );
+
+        let text = "日本語の件名です。とても長い件名なので、いくつかのエンコードされた単語に分割されます。 and some ASCII ééééééééééééééééééééééééééééééééééé";
+        for context in [
+            EncodedWordContext::Unstructured,
+            EncodedWordContext::Phrase,
+            EncodedWordContext::Comment,
+        ] {
+            let encoded = encode(text, &context);
+            for word in encoded.split(' ') {
+                assert!(word.len() <= 75);
+                if word.starts_with("=?") {
+                    // each word must be decodable on its own
+                    encoded_word(word.as_bytes()).unwrap();
+                }
+            }
+            assert_eq!(
+                mime_unstructured::<false>(encoded.as_bytes()).unwrap().1,
+                text
+            );
+        }
+
+        for text in ["é  é", "é   é  a", "a  é  b", "é ☃  é"] {
+            let encoded = encode(text, &context);
+            assert_eq!(
+                mime_unstructured::<false>(encoded.as_bytes()).unwrap().1,
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn header_segments_test() {
+        let segments = header_segments(b"Re: =?utf-8*fr?Q?Bonjour_?=\r\n =?UTF-8*FR?B?w6AgdG91cw==?= and =?iso-8859-1?Q?caf=E9?=  ");
+        assert_eq!(
+            segments,
+            vec![
+                HeaderSegment {
+                    text: "Re: ".into(),
+                    charset: None,
+                    language: None
+                },
+                HeaderSegment {
+                    text: "Bonjour à tous".into(),
+                    charset: Some("utf-8".into()),
+                    language: Some("fr".into())
+                },
+                HeaderSegment {
+                    text: " and ".into(),
+                    charset: None,
+                    language: None
+                },
+                HeaderSegment {
+                    text: "café".into(),
+                    charset: Some("iso-8859-1".into()),
+                    language: None
+                },
+            ]
+        );
+        assert!(header_segments(b"").is_empty());
+
+        let segments = header_segments(b"=?utf-8?q?caf=C3=A9?= is \x01 =?utf-8?q?ok?=");
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "café");
+        assert_eq!(segments[1].text, " is \x01 =?utf-8?q?ok?=");
+    }
+
     #[test]
     fn encoded_word_test() {
         assert_eq!(
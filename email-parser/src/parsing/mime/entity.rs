@@ -1,6 +1,5 @@
 use crate::{parsing::fields::unknown, prelude::*};
 use std::borrow::Cow;
-use std::collections::HashMap;
 
 use super::multipart;
 
@@ -48,37 +47,37 @@ pub fn entity<'a>(raw_entity: &'a RawEntity<'a>) -> Result<Entity<'a>, Error> {
         });
     }
 
-    if raw_entity.mime_type == ContentType::Text {
-        use textcode::*;
+    if raw_entity.mime_type == ContentType::Message {
+        // RFC 6532 differs from RFC 822 by allowing UTF-8 in header fields
+        // and a content transfer encoding on the encapsulated message.
+        // Decoding already happened, but UTF-8 must be accepted whatever the features.
+        // A forwarded message that cannot be parsed does not invalidate its parent.
+        let message = match raw_entity.subtype.as_ref() {
+            "rfc822" => Some(Email::parse(raw_entity.value.as_ref())),
+            "global" => Some(Email::parse_with::<true>(raw_entity.value.as_ref())),
+            _ => None,
+        };
+        if let Some(message) = message {
+            return Ok(match message {
+                Ok(message) => Entity::Message(Box::new(message)),
+                Err(_) => Entity::Unknown,
+            });
+        }
 
+        if raw_entity.subtype == "external-body" {
+            return Ok(Entity::ExternalBody(Box::new(
+                super::external_body::external_body(raw_entity)?,
+            )));
+        }
+    }
+
+    if raw_entity.mime_type == ContentType::Text {
         // TODO: auto charset for plain text
 
         if let Some(charset) = raw_entity.parameters.get("charset") {
-            let charset = charset.to_lowercase();
-
-            let value: Cow<str> = match charset.as_str() {
-                "utf-8" | "us-ascii" => Cow::Borrowed(
-                    std::str::from_utf8(raw_entity.value.as_ref())
-                        .map_err(|_| Error::Unknown("Invalid text encoding"))?,
-                ),
-                "iso-8859-1" => Cow::Owned(iso8859_1::decode_to_string(&raw_entity.value)),
-                "iso-8859-2" => Cow::Owned(iso8859_2::decode_to_string(&raw_entity.value)),
-                "iso-8859-3" => Cow::Owned(iso8859_3::decode_to_string(&raw_entity.value)),
-                "iso-8859-4" => Cow::Owned(iso8859_4::decode_to_string(&raw_entity.value)),
-                "iso-8859-5" => Cow::Owned(iso8859_5::decode_to_string(&raw_entity.value)),
-                "iso-8859-6" => Cow::Owned(iso8859_6::decode_to_string(&raw_entity.value)),
-                "iso-8859-7" => Cow::Owned(iso8859_7::decode_to_string(&raw_entity.value)),
-                "iso-8859-8" => Cow::Owned(iso8859_8::decode_to_string(&raw_entity.value)),
-                "iso-8859-9" => Cow::Owned(iso8859_9::decode_to_string(&raw_entity.value)),
-                "iso-8859-10" => Cow::Owned(iso8859_10::decode_to_string(&raw_entity.value)),
-                "iso-8859-11" => Cow::Owned(iso8859_11::decode_to_string(&raw_entity.value)),
-                "iso-8859-13" => Cow::Owned(iso8859_13::decode_to_string(&raw_entity.value)),
-                "iso-8859-14" => Cow::Owned(iso8859_14::decode_to_string(&raw_entity.value)),
-                "iso-8859-15" => Cow::Owned(iso8859_15::decode_to_string(&raw_entity.value)),
-                "iso-8859-16" => Cow::Owned(iso8859_16::decode_to_string(&raw_entity.value)),
-                "iso-6937" => Cow::Owned(iso6937::decode_to_string(&raw_entity.value)),
-                "gb2312" => Cow::Owned(gb2312::decode_to_string(&raw_entity.value)),
-                _ => return Ok(Entity::Unknown),
+            let value = match decode_text(Cow::Borrowed(raw_entity.value.as_ref()), charset)? {
+                Some(value) => value,
+                None => return Ok(Entity::Unknown),
             };
 
             return Ok(Entity::Text {
@@ -91,6 +90,43 @@ pub fn entity<'a>(raw_entity: &'a RawEntity<'a>) -> Result<Entity<'a>, Error> {
     Ok(Entity::Unknown)
 }
 
+/// Decodes text in the given charset.\
+/// Returns `None` if the charset is not supported.
+pub fn decode_text<'a>(value: Cow<'a, [u8]>, charset: &str) -> Result<Option<Cow<'a, str>>, Error> {
+    use textcode::*;
+
+    let charset = charset.to_lowercase();
+
+    Ok(Some(match charset.as_str() {
+        "utf-8" | "us-ascii" => match value {
+            Cow::Borrowed(value) => Cow::Borrowed(
+                std::str::from_utf8(value).map_err(|_| Error::Unknown("Invalid text encoding"))?,
+            ),
+            Cow::Owned(value) => Cow::Owned(
+                String::from_utf8(value).map_err(|_| Error::Unknown("Invalid text encoding"))?,
+            ),
+        },
+        "iso-8859-1" => Cow::Owned(iso8859_1::decode_to_string(&value)),
+        "iso-8859-2" => Cow::Owned(iso8859_2::decode_to_string(&value)),
+        "iso-8859-3" => Cow::Owned(iso8859_3::decode_to_string(&value)),
+        "iso-8859-4" => Cow::Owned(iso8859_4::decode_to_string(&value)),
+        "iso-8859-5" => Cow::Owned(iso8859_5::decode_to_string(&value)),
+        "iso-8859-6" => Cow::Owned(iso8859_6::decode_to_string(&value)),
+        "iso-8859-7" => Cow::Owned(iso8859_7::decode_to_string(&value)),
+        "iso-8859-8" => Cow::Owned(iso8859_8::decode_to_string(&value)),
+        "iso-8859-9" => Cow::Owned(iso8859_9::decode_to_string(&value)),
+        "iso-8859-10" => Cow::Owned(iso8859_10::decode_to_string(&value)),
+        "iso-8859-11" => Cow::Owned(iso8859_11::decode_to_string(&value)),
+        "iso-8859-13" => Cow::Owned(iso8859_13::decode_to_string(&value)),
+        "iso-8859-14" => Cow::Owned(iso8859_14::decode_to_string(&value)),
+        "iso-8859-15" => Cow::Owned(iso8859_15::decode_to_string(&value)),
+        "iso-8859-16" => Cow::Owned(iso8859_16::decode_to_string(&value)),
+        "iso-6937" => Cow::Owned(iso6937::decode_to_string(&value)),
+        "gb2312" => Cow::Owned(gb2312::decode_to_string(&value)),
+        _ => return Ok(None),
+    }))
+}
+
 pub fn header_part_owned(
     input: &[u8],
 ) -> Result<
@@ -100,7 +136,7 @@ pub fn header_part_owned(
             ContentTransferEncoding<'static>,
             ContentType<'static>,
             Cow<'static, str>,
-            HashMap<Cow<'static, str>, Cow<'static, str>>,
+            Parameters<'static>,
             Vec<(Cow<'static, str>, Cow<'static, str>)>,
             Option<(Cow<'static, str>, Cow<'static, str>)>,
             Option<Cow<'static, str>>,
@@ -117,9 +153,7 @@ pub fn header_part_owned(
             ct.into_owned(),
             mt.into_owned(),
             Cow::Owned(st.into_owned()),
-            p.into_iter()
-                .map(|(n, v)| (Cow::Owned(n.into_owned()), Cow::Owned(v.into_owned())))
-                .collect(),
+            p.into_owned(),
             ah.into_iter()
                 .map(|(n, v)| (Cow::Owned(n.into_owned()), Cow::Owned(v.into_owned())))
                 .collect(),
@@ -139,7 +173,7 @@ pub fn header_part(
             ContentTransferEncoding,
             ContentType,
             Cow<str>,
-            HashMap<Cow<str>, Cow<str>>,
+            Parameters,
             Vec<(Cow<str>, Cow<str>)>,
             Option<(Cow<str>, Cow<str>)>,
             Option<Cow<str>>,
@@ -156,28 +190,30 @@ pub fn header_part(
     let mut additional_headers = Vec::new();
 
     loop {
-        if let Ok((new_input, content_transfer_encoding)) = content_transfer_encoding(input) {
+        if let Ok((new_input, content_transfer_encoding)) =
+            content_transfer_encoding::<UTF8_HEADERS>(input)
+        {
             input = new_input;
             encoding = Some(content_transfer_encoding);
-        } else if let Ok((new_input, content_type)) = content_type(input) {
+        } else if let Ok((new_input, content_type)) = content_type::<UTF8_HEADERS>(input) {
             input = new_input;
             mime_type = Some(content_type);
-        } else if let Ok((new_input, cid)) = content_id(input) {
+        } else if let Ok((new_input, cid)) = content_id::<UTF8_HEADERS>(input) {
             input = new_input;
             id = Some(cid);
-        } else if let Ok((new_input, cdescription)) = content_description(input) {
+        } else if let Ok((new_input, cdescription)) = content_description::<UTF8_HEADERS>(input) {
             input = new_input;
             description = Some(cdescription);
         } else {
             if cfg!(feature = "content-disposition") {
-                if let Ok((new_input, cdisposition)) = content_disposition(input) {
+                if let Ok((new_input, cdisposition)) = content_disposition::<UTF8_HEADERS>(input) {
                     input = new_input;
                     disposition = Some(cdisposition);
                     continue;
                 }
             }
 
-            if let Ok((new_input, (name, value))) = unknown(input) {
+            if let Ok((new_input, (name, value))) = unknown::<UTF8_HEADERS>(input) {
                 input = new_input;
                 additional_headers.push((Cow::Borrowed(name), value));
                 continue;
@@ -315,6 +351,50 @@ mod tests {
         println!("{:?}", raw_entity(Cow::Borrowed(b"Content-type: multipart/alternative; boundary=\"simple boundary\"\r\n\r\nThis is the preamble.  It is to be ignored, though it\r\nis a handy place for composition agents to include an\r\nexplanatory note to non-MIME conformant readers.\r\n\r\n--simple boundary\r\n\r\nThis is implicitly typed plain US-ASCII text.\r\nIt does NOT end with a linebreak.\r\n--simple boundary\r\nContent-type: text/plain; charset=us-ascii\r\n\r\nThis is explicitly typed plain US-ASCII text.\r\nIt DOES end with a linebreak.\r\n\r\n--simple boundary--\r\n\r\nThis is the epilogue.  It is also to be ignored.")).unwrap());
     }
 
+    #[test]
+    fn message_entity_test() {
+        let entity = raw_entity(Cow::Borrowed(b"Content-Type: message/rfc822\r\n\r\nFrom: Mubelotix <mubelotix@mubelotix.dev>\r\nSubject:Forwarded\r\nDate: 5 May 2003 18:58:34 +0000\r\n\r\nHey!\r\n")).unwrap();
+        let message = match entity.parse().unwrap() {
+            Entity::Message(message) => message,
+            _ => panic!("Expected a message entity"),
+        };
+        assert_eq!(message.subject.unwrap(), "Forwarded");
+        assert_eq!(message.sender.address.domain, "mubelotix.dev");
+        assert_eq!(
+            Entity::Text {
+                subtype: &"plain".into(),
+                value: "Hey!\r\n".into()
+            },
+            message.mime_entity.parse().unwrap()
+        );
+
+        let entity = raw_entity(Cow::Borrowed(b"Content-Type: message/global\r\nContent-Transfer-Encoding: base64\r\n\r\nRnJvbTogbXViZWxvdGl4QG11YmVsb3RpeC5kZXYNClN1YmplY3Q6R2xvYmFsDQpEYXRlOiA1IE1heSAyMDAzIDE4OjU4OjM0ICswMDAwDQoNCkhleSENCg==")).unwrap();
+        assert!(
+            matches!(entity.parse().unwrap(), Entity::Message(message) if message.subject.as_deref() == Some("Global"))
+        );
+
+        let entity = raw_entity(Cow::Borrowed("Content-Type: message/global\r\n\r\nFrom: José <josé@exämple.de>\r\nSubject:Grüße\r\nDate: 5 May 2003 18:58:34 +0000\r\n\r\nHey!\r\n".as_bytes())).unwrap();
+        match entity.parse().unwrap() {
+            Entity::Message(message) => {
+                assert_eq!(message.subject.as_deref(), Some("Grüße"));
+                assert_eq!(message.sender.address.local_part, "josé");
+            }
+            _ => panic!("Expected a message"),
+        }
+
+        let entity = raw_entity(Cow::Borrowed(
+            b"Content-Type: message/rfc822\r\n\r\nSubject: No sender\r\n\r\nHey!\r\n",
+        ))
+        .unwrap();
+        assert_eq!(entity.parse().unwrap(), Entity::Unknown);
+
+        let entity = raw_entity(Cow::Borrowed(
+            b"Content-Type: message/global\r\n\r\nnot a message at all",
+        ))
+        .unwrap();
+        assert_eq!(entity.parse().unwrap(), Entity::Unknown);
+    }
+
     #[test]
     fn entity_test() {
         assert_eq!(Entity::Text {
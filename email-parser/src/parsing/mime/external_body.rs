@@ -0,0 +1,156 @@
+use crate::prelude::*;
+use std::borrow::Cow;
+
+pub fn access_type(value: &str) -> AccessType<'_> {
+    match value.to_ascii_lowercase().as_str() {
+        "ftp" => AccessType::Ftp,
+        "anon-ftp" => AccessType::AnonFtp,
+        "tftp" => AccessType::Tftp,
+        "local-file" => AccessType::LocalFile,
+        "mail-server" => AccessType::MailServer,
+        "url" => AccessType::Url,
+        _ => AccessType::Unknown(lowercase(Cow::Borrowed(value))),
+    }
+}
+
+pub fn external_body<'a>(raw_entity: &'a RawEntity<'a>) -> Result<ExternalBody<'a>, Error> {
+    let parameter = |name: &str| {
+        raw_entity
+            .parameters
+            .get(name)
+            .map(|value| Cow::Borrowed(value.as_ref()))
+    };
+
+    let access_type = access_type(
+        raw_entity
+            .parameters
+            .get("access-type")
+            .ok_or(Error::Unknown("Missing access-type parameter"))?,
+    );
+
+    let expiration = match raw_entity.parameters.get("expiration") {
+        Some(expiration) => Some(
+            crate::parsing::time::date_time::<UTF8_HEADERS>(expiration.as_bytes())
+                .map_err(|_| Error::Unknown("Invalid expiration parameter"))?
+                .1,
+        ),
+        None => None,
+    };
+
+    let size = match raw_entity.parameters.get("size") {
+        Some(size) => Some(
+            size.parse::<usize>()
+                .map_err(|_| Error::Unknown("Invalid size parameter"))?,
+        ),
+        None => None,
+    };
+
+    // The URL may have been folded with whitespaces that are not part of it.
+    // [Learn more](https://tools.ietf.org/html/rfc2017#section-3)
+    let url = raw_entity.parameters.get("url").map(|url| {
+        if url.contains(|c: char| c.is_ascii_whitespace()) {
+            Cow::Owned(url.split_ascii_whitespace().collect::<String>())
+        } else {
+            Cow::Borrowed(url.as_ref())
+        }
+    });
+
+    let input = raw_entity.value.as_ref();
+    let (
+        len,
+        (
+            encoding,
+            mime_type,
+            subtype,
+            parameters,
+            additional_headers,
+            id,
+            description,
+            _disposition,
+        ),
+    ) = super::entity::header_part(input)?;
+
+    Ok(ExternalBody {
+        access_type,
+        expiration,
+        size,
+        permission: parameter("permission").map(lowercase),
+        name: parameter("name"),
+        site: parameter("site"),
+        directory: parameter("directory"),
+        mode: parameter("mode"),
+        server: parameter("server"),
+        subject: parameter("subject"),
+        url,
+        content_transfer_encoding: encoding,
+        entity: RawEntity {
+            mime_type,
+            subtype,
+            description,
+            id,
+            parameters,
+            #[cfg(feature = "content-disposition")]
+            disposition: _disposition,
+            value: Cow::Borrowed(&input[input.len() - len..]),
+            additional_headers,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::mime::entity::raw_entity;
+
+    #[test]
+    fn test_external_body() {
+        let entity = raw_entity(Cow::Borrowed(b"Content-Type: message/external-body; access-type=local-file;\r\n name=\"/u/nsb/Me.jpeg\"; site=\"thumper.bellcore.com\";\r\n expiration=\"Fri, 14 Jun 1991 19:13:14 -0400\"; size=4096\r\n\r\nContent-type: image/jpeg\r\nContent-ID: <id42@guppylake.bellcore.com>\r\nContent-Transfer-Encoding: base64\r\n\r\n")).unwrap();
+        let external_body = match entity.parse().unwrap() {
+            Entity::ExternalBody(external_body) => external_body,
+            _ => panic!("Expected an external-body entity"),
+        };
+        assert_eq!(external_body.access_type, AccessType::LocalFile);
+        assert_eq!(external_body.name.unwrap(), "/u/nsb/Me.jpeg");
+        assert_eq!(external_body.site.unwrap(), "thumper.bellcore.com");
+        assert_eq!(external_body.size, Some(4096));
+        assert_eq!(external_body.expiration.unwrap().date.year, 1991);
+        assert_eq!(
+            external_body.content_transfer_encoding,
+            ContentTransferEncoding::Base64
+        );
+        assert_eq!(external_body.entity.mime_type, ContentType::Image);
+        assert_eq!(external_body.entity.subtype, "jpeg");
+        assert_eq!(external_body.entity.id.unwrap().0, "id42");
+        assert!(external_body.entity.value.is_empty());
+
+        let entity = raw_entity(Cow::Borrowed(b"Content-Type: message/external-body; access-type=URL;\r\n URL=\"ftp://cs.utk.edu/pub/moore/\r\n bulk-mailer/bulk-mailer.tar\"\r\n\r\nContent-Type: application/octet-stream\r\n\r\n")).unwrap();
+        let external_body = match entity.parse().unwrap() {
+            Entity::ExternalBody(external_body) => external_body,
+            _ => panic!("Expected an external-body entity"),
+        };
+        assert_eq!(external_body.access_type, AccessType::Url);
+        assert_eq!(
+            external_body.url.unwrap(),
+            "ftp://cs.utk.edu/pub/moore/bulk-mailer/bulk-mailer.tar"
+        );
+
+        let entity = raw_entity(Cow::Borrowed(b"Content-Type: message/external-body; access-type=mail-server;\r\n server=\"listserv@bogus.bitnet\"\r\n\r\nContent-type: application/atomicmail\r\n\r\nget RFC-1234\r\n")).unwrap();
+        let external_body = match entity.parse().unwrap() {
+            Entity::ExternalBody(external_body) => external_body,
+            _ => panic!("Expected an external-body entity"),
+        };
+        assert_eq!(external_body.access_type, AccessType::MailServer);
+        assert_eq!(external_body.server.unwrap(), "listserv@bogus.bitnet");
+        assert_eq!(
+            external_body.entity.value,
+            Cow::Borrowed(b"get RFC-1234\r\n")
+        );
+
+        assert!(raw_entity(Cow::Borrowed(
+            b"Content-Type: message/external-body\r\n\r\nContent-type: image/jpeg\r\n\r\n"
+        ))
+        .unwrap()
+        .parse()
+        .is_err());
+    }
+}
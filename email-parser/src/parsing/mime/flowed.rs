@@ -0,0 +1,158 @@
+/// Splits a line into its quote depth and its content.\
+/// [Learn more](https://tools.ietf.org/html/rfc3676#section-4.5)
+fn quote_depth(line: &str) -> (usize, &str) {
+    let content = line.trim_start_matches('>');
+    (line.len() - content.len(), content)
+}
+
+/// Returns the line prefixed by its quote marks, stuffed with a space when needed.\
+/// [Learn more](https://tools.ietf.org/html/rfc3676#section-4.4)
+fn stuffed_line(depth: usize, content: &str) -> String {
+    let mut line = ">".repeat(depth);
+    if (depth > 0 && !content.is_empty())
+        || content.starts_with(' ')
+        || content.starts_with('>')
+        || content.starts_with("From ")
+    {
+        line.push(' ');
+    }
+    line.push_str(content);
+    line
+}
+
+/// Unwraps flowed lines, removes space-stuffing and keeps quote depths.\
+/// Quoted lines are prefixed by one `>` per level followed by a space.\
+/// [Learn more](https://tools.ietf.org/html/rfc3676#section-4.2)
+pub fn decode(text: &str, delsp: bool) -> String {
+    let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut output = String::with_capacity(text.len());
+    let mut paragraph: Option<(usize, String)> = None;
+
+    let flush = |output: &mut String, depth: usize, content: &str| {
+        output.push_str(&">".repeat(depth));
+        if depth > 0 && !content.is_empty() {
+            output.push(' ');
+        }
+        output.push_str(content);
+        output.push_str(newline);
+    };
+
+    for line in text.lines() {
+        let (depth, content) = quote_depth(line);
+        let content = content.strip_prefix(' ').unwrap_or(content);
+        let is_flowed = content.ends_with(' ') && content != "-- ";
+
+        // A change of quote depth ends the paragraph, even if the previous line was flowed.
+        if let Some((paragraph_depth, paragraph_content)) = &paragraph {
+            if *paragraph_depth != depth {
+                flush(&mut output, *paragraph_depth, paragraph_content);
+                paragraph = None;
+            }
+        }
+
+        let content = if is_flowed && delsp {
+            &content[..content.len() - 1]
+        } else {
+            content
+        };
+        let (_, paragraph_content) = paragraph.get_or_insert_with(|| (depth, String::new()));
+        paragraph_content.push_str(content);
+
+        if !is_flowed {
+            if let Some((depth, content)) = paragraph.take() {
+                flush(&mut output, depth, &content);
+            }
+        }
+    }
+    if let Some((depth, content)) = paragraph.take() {
+        flush(&mut output, depth, &content);
+    }
+
+    if !text.ends_with('\n') && output.ends_with(newline) {
+        output.truncate(output.len() - newline.len());
+    }
+    output
+}
+
+/// Wraps lines longer than `width` characters with soft line breaks, space-stuffing lines when needed.\
+/// Lines are split between words, so words longer than a line are never broken.\
+/// [Learn more](https://tools.ietf.org/html/rfc3676#section-4.1)
+pub fn encode(text: &str, width: usize, delsp: bool) -> String {
+    let mut output = String::with_capacity(text.len() + text.len() / width.max(1) * 3);
+
+    for line in text.lines() {
+        let (depth, content) = quote_depth(line);
+        let content = if depth > 0 {
+            content.strip_prefix(' ').unwrap_or(content)
+        } else {
+            content
+        };
+
+        // The signature separator is the only line allowed to end with a space without being flowed.
+        if content == "-- " {
+            output.push_str(&stuffed_line(depth, content));
+            output.push_str("\r\n");
+            continue;
+        }
+
+        let content = content.trim_end_matches(' ');
+        let mut current = String::new();
+        for word in content.split_inclusive(' ') {
+            let prefix_len =
+                stuffed_line(depth, &current).chars().count() - current.chars().count();
+            let len = prefix_len + current.chars().count() + word.chars().count() + delsp as usize;
+            if !current.trim_start_matches(' ').is_empty() && len > width {
+                output.push_str(&stuffed_line(depth, &current));
+                if delsp {
+                    output.push(' ');
+                }
+                output.push_str("\r\n");
+                current.clear();
+            }
+            current.push_str(word);
+        }
+        output.push_str(&stuffed_line(depth, &current));
+        output.push_str("\r\n");
+    }
+
+    if !text.ends_with('\n') && output.ends_with("\r\n") {
+        output.truncate(output.len() - 2);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode("This is a flowed \r\nparagraph.\r\n\r\nThis line is fixed.\r\n>> Quoted \r\n>> text\r\n> Less \r\nquoted\r\n From the start\r\n-- \r\nSignature\r\n", false),
+            "This is a flowed paragraph.\r\n\r\nThis line is fixed.\r\n>> Quoted text\r\n> Less \r\nquoted\r\nFrom the start\r\n-- \r\nSignature\r\n"
+        );
+        assert_eq!(decode("日本 \r\n語", true), "日本語");
+        assert_eq!(decode("Flowed \r\nlast line ", false), "Flowed last line ");
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode("The quick brown fox jumps over the lazy dog.\n> Quoted text that is long\n>> From me\n\nFrom here  \n-- \n", 20, false),
+            "The quick brown fox \r\njumps over the lazy \r\ndog.\r\n> Quoted text that \r\n> is long\r\n>> From me\r\n\r\n From here\r\n-- \r\n"
+        );
+        assert_eq!(encode("aaa bbb ccc", 8, true), "aaa  \r\nbbb ccc");
+        assert_eq!(
+            encode("Supercalifragilistic word", 10, false),
+            "Supercalifragilistic \r\nword"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.\r\n\r\n> Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.\r\n>> From a deeply quoted line\r\n  Indented text\r\n";
+        for delsp in [false, true] {
+            assert_eq!(decode(&encode(text, 30, delsp), delsp), text);
+        }
+    }
+}
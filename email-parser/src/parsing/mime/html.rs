@@ -0,0 +1,568 @@
+use crate::mime::HtmlToText;
+
+/// Decodes an HTML character reference (without the leading `&` and the trailing `;`).
+fn entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number
+            .strip_prefix('x')
+            .or_else(|| number.strip_prefix('X'))
+        {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse::<u32>().ok()?,
+        };
+        return std::char::from_u32(code);
+    }
+
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => None,
+    }
+}
+
+/// Replaces character references by the characters they represent.\
+/// Unknown references are left untouched.
+pub fn decode_entities(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(idx) = rest.find('&') {
+        output.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| entity(&rest[1..end + 1]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                output.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+
+    output
+}
+
+/// Parses the attributes of a tag (the content between `<` and `>`).\
+/// Names are returned in lowercase and values are decoded.
+pub fn attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag
+        .trim_start_matches('/')
+        .trim_start_matches(|c: char| !c.is_whitespace() && c != '/');
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            return attributes;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let value = match rest.strip_prefix('=') {
+            Some(value) => {
+                let value = value.trim_start();
+                match value.chars().next() {
+                    Some(quote) if quote == '"' || quote == '\'' => {
+                        let end = value[1..]
+                            .find(quote)
+                            .map(|end| end + 1)
+                            .unwrap_or(value.len());
+                        rest = value.get(end + 1..).unwrap_or("");
+                        &value[1..end]
+                    }
+                    _ => {
+                        let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                        rest = &value[end..];
+                        &value[..end]
+                    }
+                }
+            }
+            None => "",
+        };
+
+        attributes.push((name, decode_entities(value)));
+    }
+}
+
+/// Returns the value of an attribute of a tag (the content between `<` and `>`).
+pub fn attribute(tag: &str, name: &str) -> Option<String> {
+    attributes(tag)
+        .into_iter()
+        .find(|(attribute_name, _)| attribute_name.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+}
+
+/// A list being rendered.
+struct List {
+    /// The number of the next item, if the list is ordered.
+    counter: Option<usize>,
+    /// The width of the marker of the current item, used to indent the following lines.
+    marker_width: usize,
+}
+
+/// The state of an HTML to text conversion.\
+/// Text is written word by word and line breaks are only inserted before the next word, so that trailing ones are dropped.
+struct Renderer<'o> {
+    options: &'o HtmlToText,
+    /// Whether lines should be prefixed by `>` in blockquotes and by markers in lists.
+    prefixes: bool,
+    lines: Vec<String>,
+    line: String,
+    line_has_text: bool,
+    /// The number of line breaks to insert before the next word.
+    breaks: usize,
+    /// Whether a space should be inserted before the next word.
+    space: bool,
+    quote: usize,
+    last_quote: usize,
+    lists: Vec<List>,
+    marker: Option<String>,
+    pre: usize,
+    links: Vec<String>,
+    link: Option<(String, String)>,
+}
+
+impl<'o> Renderer<'o> {
+    fn new(options: &'o HtmlToText, prefixes: bool) -> Renderer<'o> {
+        Renderer {
+            options,
+            prefixes,
+            lines: Vec::new(),
+            line: String::new(),
+            line_has_text: false,
+            breaks: 0,
+            space: false,
+            quote: 0,
+            last_quote: 0,
+            lists: Vec::new(),
+            marker: None,
+            pre: 0,
+            links: Vec::new(),
+            link: None,
+        }
+    }
+
+    fn quote_prefix(&self, depth: usize) -> String {
+        if self.prefixes {
+            "> ".repeat(depth)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Makes sure there are at least `count` line breaks before the next word.
+    fn line_break(&mut self, count: usize) {
+        self.breaks = self.breaks.max(count);
+        self.space = false;
+    }
+
+    /// Inserts the pending line breaks and the prefix of the line if needed.
+    fn begin_text(&mut self) {
+        if self.breaks > 0 && self.line_has_text {
+            self.lines.push(std::mem::take(&mut self.line));
+            let blank_line = self
+                .quote_prefix(self.quote.min(self.last_quote))
+                .trim_end()
+                .to_string();
+            for _ in 1..self.breaks {
+                self.lines.push(blank_line.clone());
+            }
+            self.line_has_text = false;
+        }
+        self.breaks = 0;
+
+        if !self.line_has_text {
+            self.line = self.quote_prefix(self.quote);
+            if let Some((last, others)) = self.lists.split_last().filter(|_| self.prefixes) {
+                for list in others {
+                    self.line.push_str(&" ".repeat(list.marker_width));
+                }
+                match self.marker.take() {
+                    Some(marker) => self.line.push_str(&marker),
+                    None => self.line.push_str(&" ".repeat(last.marker_width)),
+                }
+            }
+            self.line_has_text = true;
+            self.last_quote = self.quote;
+        } else if self.space {
+            self.line.push(' ');
+            if let Some((_, text)) = self.link.as_mut() {
+                text.push(' ');
+            }
+        }
+        self.space = false;
+    }
+
+    fn write(&mut self, text: &str) {
+        self.begin_text();
+        self.line.push_str(text);
+        if let Some((_, link_text)) = self.link.as_mut() {
+            link_text.push_str(text);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = decode_entities(text);
+
+        if self.pre > 0 {
+            for (idx, line) in text.split('\n').enumerate() {
+                if idx > 0 {
+                    self.breaks += 1;
+                }
+                let line = line.trim_end_matches('\r');
+                if !line.is_empty() {
+                    self.write(line);
+                }
+            }
+            return;
+        }
+
+        if text.starts_with(char::is_whitespace) {
+            self.space = true;
+        }
+        for word in text.split_whitespace() {
+            self.write(word);
+            self.space = true;
+        }
+        self.space = text.ends_with(char::is_whitespace) || (self.space && text.is_empty());
+    }
+
+    fn open_link(&mut self, tag: &str) {
+        self.link = attribute(tag, "href").map(|href| (href.trim().to_string(), String::new()));
+    }
+
+    /// Adds a footnote reference after the text of a link.\
+    /// Internal links and links whose text already is the URL don't need footnotes.
+    fn close_link(&mut self) {
+        let (href, text) = match self.link.take() {
+            Some(link) => link,
+            None => return,
+        };
+        if !self.options.link_footnotes
+            || href.is_empty()
+            || href.starts_with('#')
+            || href.to_ascii_lowercase().starts_with("javascript:")
+            || text.trim() == href
+            || Some(text.trim()) == href.strip_prefix("mailto:")
+        {
+            return;
+        }
+
+        let number = match self.links.iter().position(|link| link == &href) {
+            Some(idx) => idx + 1,
+            None => {
+                self.links.push(href);
+                self.links.len()
+            }
+        };
+        self.space = false;
+        self.write(&format!("[{}]", number));
+    }
+
+    fn open_list(&mut self, tag: &str, ordered: bool) {
+        self.line_break(if self.lists.is_empty() { 2 } else { 1 });
+        let counter = if ordered {
+            Some(
+                attribute(tag, "start")
+                    .and_then(|start| start.trim().parse().ok())
+                    .unwrap_or(1),
+            )
+        } else {
+            None
+        };
+        self.lists.push(List {
+            counter,
+            marker_width: 0,
+        });
+    }
+
+    fn close_list(&mut self) {
+        self.lists.pop();
+        self.marker = None;
+        self.line_break(if self.lists.is_empty() { 2 } else { 1 });
+    }
+
+    fn open_item(&mut self) {
+        self.line_break(1);
+        if let Some(list) = self.lists.last_mut() {
+            let marker = match list.counter.as_mut() {
+                Some(counter) => {
+                    *counter += 1;
+                    format!("{}. ", *counter - 1)
+                }
+                None => String::from("* "),
+            };
+            list.marker_width = marker.chars().count();
+            self.marker = Some(marker);
+        }
+    }
+
+    fn tag(&mut self, tag: &str, name: &str) {
+        let closing = tag.starts_with('/');
+        match name {
+            "br" => self.breaks += 1,
+            "a" if closing => self.close_link(),
+            "a" => self.open_link(tag),
+            "img" if !closing => {
+                if let Some(alt) = attribute(tag, "alt") {
+                    self.text(&alt);
+                }
+            }
+            "ul" | "ol" if closing => self.close_list(),
+            "ul" => self.open_list(tag, false),
+            "ol" => self.open_list(tag, true),
+            "li" if closing => self.line_break(1),
+            "li" => self.open_item(),
+            "blockquote" => {
+                self.line_break(2);
+                if closing {
+                    self.quote = self.quote.saturating_sub(1);
+                } else {
+                    self.quote += 1;
+                }
+            }
+            "pre" => {
+                self.line_break(2);
+                if closing {
+                    self.pre = self.pre.saturating_sub(1);
+                } else {
+                    self.pre += 1;
+                }
+            }
+            "td" | "th" => self.space = self.line_has_text,
+            "div" | "tr" | "hr" | "dt" | "dd" => self.line_break(1),
+            "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "table" | "dl" => self.line_break(2),
+            _ => (),
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if self.line_has_text {
+            self.lines.push(self.line);
+        }
+        if !self.links.is_empty() {
+            self.lines.push(String::new());
+            for (idx, link) in self.links.iter().enumerate() {
+                self.lines.push(format!("[{}] {}", idx + 1, link));
+            }
+        }
+
+        let mut output = String::new();
+        for (idx, line) in self.lines.iter().enumerate() {
+            if idx > 0 {
+                output.push('\n');
+            }
+            output.push_str(line.trim_end());
+        }
+        output
+    }
+}
+
+fn render(html: &str, renderer: &mut Renderer) {
+    let mut rest = html;
+
+    while let Some(idx) = rest.find('<') {
+        renderer.text(&rest[..idx]);
+        rest = &rest[idx..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment
+                .find("-->")
+                .map(|end| &comment[end + 3..])
+                .unwrap_or("");
+            continue;
+        }
+
+        if !rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!') {
+            renderer.text("<");
+            rest = &rest[1..];
+            continue;
+        }
+
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => return,
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        match name.as_str() {
+            "script" | "style" | "head" | "template" if !tag.starts_with('/') => {
+                let closing = format!("</{}", name);
+                rest = match rest.to_ascii_lowercase().find(&closing) {
+                    Some(idx) => &rest[idx..],
+                    None => "",
+                };
+            }
+            name => renderer.tag(tag, name),
+        }
+    }
+    renderer.text(rest);
+}
+
+/// Converts HTML to plain text.\
+/// Scripts and styles are removed, paragraphs and list items are put on their own lines, blockquotes are prefixed with `>` and links are listed as footnotes.
+pub fn convert(html: &str, options: &HtmlToText) -> String {
+    let mut renderer = Renderer::new(options, true);
+    render(html, &mut renderer);
+    renderer.finish()
+}
+
+/// Converts HTML to a single line of text truncated at a word boundary.
+pub fn snippet(html: &str, options: &HtmlToText) -> String {
+    let without_footnotes = HtmlToText {
+        link_footnotes: false,
+        ..options.clone()
+    };
+    let mut renderer = Renderer::new(&without_footnotes, false);
+    render(html, &mut renderer);
+    let text = renderer
+        .finish()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if text.chars().count() <= options.snippet_length {
+        return text;
+    }
+    if options.snippet_length == 0 {
+        return String::new();
+    }
+
+    let (end, last) = text
+        .char_indices()
+        .nth(options.snippet_length - 1)
+        .unwrap_or((text.len(), ' '));
+    let mut snippet = match text[..end + last.len_utf8()].rfind(' ') {
+        Some(space) if space > 0 => text[..space].to_string(),
+        _ => text[..end].to_string(),
+    };
+    snippet.push('…');
+    snippet
+}
+
+/// Converts HTML to plain text with the default options.
+pub fn html_to_text(html: &str) -> String {
+    convert(html, &HtmlToText::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("Tom &amp; Jerry"), "Tom & Jerry");
+        assert_eq!(decode_entities("&lt;p&gt; &#233;&#xE9;"), "<p> éé");
+        assert_eq!(decode_entities("AT&T &unknown; &"), "AT&T &unknown; &");
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        assert_eq!(
+            html_to_text("<html><head><title>Title</title><style>p { color: red; }</style></head><body><p>Hello <b>world</b>!</p><script>alert(1)</script><div>Second&nbsp;line</div><!-- <p>comment</p> --></body></html>"),
+            "Hello world!\n\nSecond line"
+        );
+        assert_eq!(
+            html_to_text("First\n line<br>Second line<p></p><p>Paragraph</p>"),
+            "First line\nSecond line\n\nParagraph"
+        );
+        assert_eq!(html_to_text("1 < 2 &amp;&amp; 3 > 2<b"), "1 < 2 && 3 > 2");
+    }
+
+    #[test]
+    fn test_attribute() {
+        let tag =
+            "a class=link HREF = 'https://example.com/?a=1&amp;b=2' title=\"Some title\" hidden";
+        assert_eq!(
+            attribute(tag, "href").unwrap(),
+            "https://example.com/?a=1&b=2"
+        );
+        assert_eq!(attribute(tag, "class").unwrap(), "link");
+        assert_eq!(attribute(tag, "title").unwrap(), "Some title");
+        assert_eq!(attribute(tag, "hidden").unwrap(), "");
+        assert!(attribute(tag, "a").is_none());
+        assert!(attribute("img/", "src").is_none());
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            html_to_text("<p>Shopping list:</p><ul><li>Apples</li><li>Pears<ol start=3><li>Green</li><li>Red and\n yellow</li></ol></li></ul><p>Done</p>"),
+            "Shopping list:\n\n* Apples\n* Pears\n  3. Green\n  4. Red and yellow\n\nDone"
+        );
+    }
+
+    #[test]
+    fn test_links() {
+        assert_eq!(
+            html_to_text("See <a href=\"https://example.com/\">our website</a>, <a href=\"https://example.com/\">again</a>, <a href=\"#top\">top</a>, <a href=\"mailto:me@example.com\">me@example.com</a> or <a href=\"https://example.org/\">this</a>."),
+            "See our website[1], again[1], top, me@example.com or this[2].\n\n[1] https://example.com/\n[2] https://example.org/"
+        );
+
+        let options = HtmlToText {
+            link_footnotes: false,
+            ..HtmlToText::default()
+        };
+        assert_eq!(
+            convert("<a href=\"https://example.com/\">Link</a>", &options),
+            "Link"
+        );
+    }
+
+    #[test]
+    fn test_blockquotes() {
+        assert_eq!(
+            html_to_text("<p>I agree.</p><blockquote><p>First</p><p>Second</p><blockquote>Nested</blockquote></blockquote><pre>let a = 1;\n  let b = 2;</pre>"),
+            "I agree.\n\n> First\n>\n> Second\n>\n> > Nested\n\nlet a = 1;\n  let b = 2;"
+        );
+    }
+
+    #[test]
+    fn test_snippet() {
+        let options = HtmlToText {
+            snippet_length: 20,
+            ..HtmlToText::default()
+        };
+        assert_eq!(
+            snippet("<style>p {}</style><p>Hello <a href=\"https://example.com/\">world</a>!</p><blockquote>How are you doing?</blockquote>", &options),
+            "Hello world! How…"
+        );
+        assert_eq!(snippet("<p>Short</p>", &options), "Short");
+        assert_eq!(
+            snippet("Supercalifragilisticexpialidocious", &options),
+            "Supercalifragilisti…"
+        );
+
+        let options = HtmlToText {
+            snippet_length: 3,
+            ..HtmlToText::default()
+        };
+        assert_eq!(snippet("abéééé", &options), "ab…");
+        assert_eq!(snippet("ééé éé", &options), "éé…");
+        assert_eq!(snippet("é é é", &options), "é…");
+    }
+}
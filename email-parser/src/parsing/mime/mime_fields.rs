@@ -1,14 +1,13 @@
 use crate::mime::*;
 use crate::prelude::*;
 use std::borrow::Cow;
-use std::collections::HashMap;
 
 #[inline]
-fn ignore_inline_cfws(input: &[u8]) -> Res<()> {
+fn ignore_inline_cfws<const UTF8: bool>(input: &[u8]) -> Res<()> {
     triplet(
         input,
         |input| take_while(input, is_wsp),
-        |input| Ok(optional(input, comment)),
+        |input| Ok(optional(input, comment::<UTF8>)),
         |input| take_while(input, is_wsp),
     )
     .map(|(i, _)| (i, ()))
@@ -21,14 +20,14 @@ fn token(input: &[u8]) -> Res<&str> {
     })
 }
 
-pub fn mime_version(input: &[u8]) -> Res<(u8, u8)> {
+pub fn mime_version<const UTF8: bool>(input: &[u8]) -> Res<(u8, u8)> {
     let (input, ()) = tag_no_case(
         input,
         b"MIME-Version:",
         b"mime-vERSION:",
         "TAG NO CASE ERROR: Header name (Mime-Version) does not match.",
     )?;
-    let (input, _) = optional(input, cfws);
+    let (input, _) = optional(input, cfws::<UTF8>);
 
     fn u8_number(input: &[u8]) -> Res<u8> {
         let (mut input, mut number) = digit(input)?;
@@ -54,7 +53,7 @@ pub fn mime_version(input: &[u8]) -> Res<(u8, u8)> {
     )?;
     let (input, d2) = u8_number(input)?;
 
-    let (input, _cwfs) = ignore_inline_cfws(input)?;
+    let (input, _cwfs) = ignore_inline_cfws::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -64,14 +63,14 @@ pub fn mime_version(input: &[u8]) -> Res<(u8, u8)> {
     Ok((input, (d1, d2)))
 }
 
-fn parameter(input: &[u8]) -> Res<(Cow<str>, Option<u8>, bool, Cow<str>)> {
-    let (input, _) = optional(input, cfws);
+fn parameter<const UTF8: bool>(input: &[u8]) -> Res<(Cow<str>, Option<u8>, bool, Cow<str>)> {
+    let (input, _) = optional(input, cfws::<UTF8>);
     let (input, ()) = tag(
         input,
         b";",
         "TAG ERROR: A MIME parameter must start with `;`.",
     )?;
-    let (input, _) = optional(input, cfws);
+    let (input, _) = optional(input, cfws::<UTF8>);
     let (input, (mut name, index, encoded)) = match_parsers(
         input,
         &mut [
@@ -137,21 +136,21 @@ fn parameter(input: &[u8]) -> Res<(Cow<str>, Option<u8>, bool, Cow<str>)> {
                 let (input, value) = token(input)?;
                 Ok((input, Cow::Borrowed(value)))
             },
-            |input| quoted_string(input),
+            |input| quoted_string::<UTF8>(input),
         ][..],
     )?;
 
     Ok((input, (name, index, encoded, value)))
 }
 
-pub fn content_type(input: &[u8]) -> Res<(ContentType, Cow<str>, HashMap<Cow<str>, Cow<str>>)> {
+pub fn content_type<const UTF8: bool>(input: &[u8]) -> Res<(ContentType, Cow<str>, Parameters)> {
     let (input, ()) = tag_no_case(
         input,
         b"Content-Type:",
         b"cONTENT-tYPE:",
         "TAG NO CASE ERROR: Header name (Content-Type) does not match.",
     )?;
-    let (input, _) = optional(input, cfws);
+    let (input, _) = optional(input, cfws::<UTF8>);
 
     let (input, mime_type) = match_parsers(
         input,
@@ -231,10 +230,10 @@ pub fn content_type(input: &[u8]) -> Res<(ContentType, Cow<str>, HashMap<Cow<str
     let (input, subtype) = token(input)?;
     let subtype = lowercase(Cow::Borrowed(subtype));
 
-    let (input, parameters_vec) = many(input, parameter)?;
+    let (input, parameters_vec) = many(input, parameter::<UTF8>)?;
     let parameters = super::percent_encoding::collect_parameters(parameters_vec)?;
 
-    let (input, ()) = ignore_inline_cfws(input)?;
+    let (input, ()) = ignore_inline_cfws::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -244,7 +243,7 @@ pub fn content_type(input: &[u8]) -> Res<(ContentType, Cow<str>, HashMap<Cow<str
     Ok((input, (mime_type, subtype, parameters)))
 }
 
-pub fn content_disposition(input: &[u8]) -> Res<Disposition> {
+pub fn content_disposition<const UTF8: bool>(input: &[u8]) -> Res<Disposition> {
     use crate::parsing::time::date_time;
 
     let (input, ()) = tag_no_case(
@@ -253,7 +252,7 @@ pub fn content_disposition(input: &[u8]) -> Res<Disposition> {
         b"cONTENT-dISPOSITION:",
         "TAG NO CASE ERROR: Header name (Content-Disposition) does not match.",
     )?;
-    let (input, _) = optional(input, cfws);
+    let (input, _) = optional(input, cfws::<UTF8>);
 
     let (mut input, disposition_type) = match_parsers(
         input,
@@ -277,7 +276,7 @@ pub fn content_disposition(input: &[u8]) -> Res<Disposition> {
 
     let mut disposition = Disposition {
         disposition_type,
-        unstructured: HashMap::new(),
+        unstructured: Parameters::new(),
         creation_date: None,
         modification_date: None,
         read_date: None,
@@ -285,10 +284,10 @@ pub fn content_disposition(input: &[u8]) -> Res<Disposition> {
     };
     let mut parameters_vec = Vec::new();
     loop {
-        fn filename_parameter(input: &[u8]) -> Res<Cow<str>> {
-            let (input, _) = optional(input, cfws);
+        fn filename_parameter<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
+            let (input, _) = optional(input, cfws::<UTF8>);
             let (input, ()) = tag(input, b";", "TAG ERROR: In a Content-Disposition header, a filename parameter must start with a `;`.")?;
-            let (input, _) = optional(input, cfws);
+            let (input, _) = optional(input, cfws::<UTF8>);
             let (input, ()) = tag_no_case(input, b"filename", b"FILENAME", "TAG NO CASE ERROR: In a Content-Disposition header, the name of the parameter does not match a filename parameter.")?;
 
             let (input, ()) = tag(input, b"=", "TAG ERROR: In a Content-Disposition header, a filename parameter value must be preceded by a `=`.")?;
@@ -299,47 +298,49 @@ pub fn content_disposition(input: &[u8]) -> Res<Disposition> {
                         let (input, value) = token(input)?;
                         Ok((input, Cow::Borrowed(value)))
                     },
-                    |input| quoted_string(input),
+                    |input| quoted_string::<UTF8>(input),
                 ][..],
             )?;
 
             Ok((input, value))
         }
 
-        fn date_parameter<'a>(
+        fn date_parameter<'a, const UTF8: bool>(
             input: &'a [u8],
             name: &'static [u8],
             name_uppercase: &'static [u8],
         ) -> Res<'a, DateTime> {
-            let (input, _) = optional(input, cfws);
+            let (input, _) = optional(input, cfws::<UTF8>);
             let (input, ()) = tag(input, b";", "TAG ERROR: In a Content-Disposition header, a date parameter must start with a `;`.")?;
-            let (input, _) = optional(input, cfws);
+            let (input, _) = optional(input, cfws::<UTF8>);
             let (input, ()) = tag_no_case(input, name, name_uppercase, "TAG NO CASE ERROR: In a Content-Disposition header, the name of the parameter does not match a date parameter.")?;
 
             let (input, ()) = tag(input, b"=\"", "TAG ERROR: In a Content-Disposition header, a date parameter value must be preceded by `=\"`.")?;
-            let (input, value) = date_time(input)?;
+            let (input, value) = date_time::<UTF8>(input)?;
             let (input, ()) = tag(input, b"\"", "TAG ERROR: In a Content-Disposition header, a date parameter value must be closed by a `\"`.")?;
 
             Ok((input, value))
         }
 
-        if let Ok((new_input, value)) = filename_parameter(input) {
+        if let Ok((new_input, value)) = filename_parameter::<UTF8>(input) {
             disposition.filename = Some(value);
             input = new_input;
         } else if let Ok((new_input, value)) =
-            date_parameter(input, b"creation-date", b"CREATION-DATE")
+            date_parameter::<UTF8>(input, b"creation-date", b"CREATION-DATE")
         {
             disposition.creation_date = Some(value);
             input = new_input;
         } else if let Ok((new_input, value)) =
-            date_parameter(input, b"modification-date", b"MODIFICATION-DATE")
+            date_parameter::<UTF8>(input, b"modification-date", b"MODIFICATION-DATE")
         {
             disposition.modification_date = Some(value);
             input = new_input;
-        } else if let Ok((new_input, value)) = date_parameter(input, b"read-date", b"READ-DATE") {
+        } else if let Ok((new_input, value)) =
+            date_parameter::<UTF8>(input, b"read-date", b"READ-DATE")
+        {
             disposition.read_date = Some(value);
             input = new_input;
-        } else if let Ok((new_input, (name, index, encoded, value))) = parameter(input) {
+        } else if let Ok((new_input, (name, index, encoded, value))) = parameter::<UTF8>(input) {
             parameters_vec.push((name, index, encoded, value));
             input = new_input;
         } else {
@@ -348,7 +349,7 @@ pub fn content_disposition(input: &[u8]) -> Res<Disposition> {
     }
     disposition.unstructured = super::percent_encoding::collect_parameters(parameters_vec)?;
 
-    let (input, ()) = ignore_inline_cfws(input)?;
+    let (input, ()) = ignore_inline_cfws::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -358,14 +359,14 @@ pub fn content_disposition(input: &[u8]) -> Res<Disposition> {
     Ok((input, disposition))
 }
 
-pub fn content_transfer_encoding(input: &[u8]) -> Res<ContentTransferEncoding> {
+pub fn content_transfer_encoding<const UTF8: bool>(input: &[u8]) -> Res<ContentTransferEncoding> {
     let (input, ()) = tag_no_case(
         input,
         b"Content-Transfer-Encoding:",
         b"cONTENT-tRANSFER-eNCODING:",
         "TAG NO CASE ERROR: Header name (Content-Transfer-Encoding) does not match.",
     )?;
-    let (input, _) = optional(input, cfws);
+    let (input, _) = optional(input, cfws::<UTF8>);
 
     let (input, encoding) = match_parsers(
         input,
@@ -399,20 +400,20 @@ pub fn content_transfer_encoding(input: &[u8]) -> Res<ContentTransferEncoding> {
         ][..],
     )?;
 
-    let (input, _cwfs) = ignore_inline_cfws(input)?;
+    let (input, _cwfs) = ignore_inline_cfws::<UTF8>(input)?;
     let (input, ()) = tag(input, b"\r\n", "TAG ERROR: A header (`Content-Transfer-Encoding` in this case) must end with a CRLF sequence.")?;
 
     Ok((input, encoding))
 }
 
-pub fn content_id(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
+pub fn content_id<const UTF8: bool>(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
     let (input, ()) = tag_no_case(
         input,
         b"Content-ID:",
         b"cONTENT-id:",
         "TAG NO CASE ERROR: Header name (Content-ID) does not match.",
     )?;
-    let (input, id) = crate::parsing::address::message_id(input)?;
+    let (input, id) = crate::parsing::address::message_id::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -422,14 +423,14 @@ pub fn content_id(input: &[u8]) -> Res<(Cow<str>, Cow<str>)> {
     Ok((input, id))
 }
 
-pub fn content_description(input: &[u8]) -> Res<Cow<str>> {
+pub fn content_description<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
     let (input, ()) = tag_no_case(
         input,
         b"Content-Description:",
         b"cONTENT-dESCRIPTION:",
         "TAG NO CASE ERROR: Header name (Content-Description) does not match.",
     )?;
-    let (input, description) = mime_unstructured(input)?;
+    let (input, description) = mime_unstructured::<UTF8>(input)?;
     let (input, ()) = tag(
         input,
         b"\r\n",
@@ -452,13 +453,13 @@ mod test {
                 creation_date: None,
                 modification_date: None,
                 read_date: None,
-                unstructured: HashMap::new()
+                unstructured: Parameters::new()
             },
-            content_disposition(b"Content-Disposition: inline\r\n")
+            content_disposition::<false>(b"Content-Disposition: inline\r\n")
                 .unwrap()
                 .1
         );
-        assert_eq!(Disposition { disposition_type: DispositionType::Attachment, filename: Some("genome.jpeg".into()), creation_date: None, modification_date: Some(DateTime { day_name: Some(Day::Wednesday), date: Date { day: 12, month: Month::February, year: 1997 }, time: TimeWithZone { time: Time { hour: 16, minute: 29, second: 51 }, zone: Zone { sign: false, hour_offset: 5, minute_offset: 0 } } }), read_date: None, unstructured: HashMap::new() }, content_disposition(b"Content-Disposition: attachment; filename=genome.jpeg;\r\n modification-date=\"Wed, 12 Feb 1997 16:29:51 -0500\"\r\n").unwrap().1);
+        assert_eq!(Disposition { disposition_type: DispositionType::Attachment, filename: Some("genome.jpeg".into()), creation_date: None, modification_date: Some(DateTime { day_name: Some(Day::Wednesday), date: Date { day: 12, month: Month::February, year: 1997 }, time: TimeWithZone { time: Time { hour: 16, minute: 29, second: 51 }, zone: Zone { sign: false, hour_offset: 5, minute_offset: 0 } } }), read_date: None, unstructured: Parameters::new() }, content_disposition::<false>(b"Content-Disposition: attachment; filename=genome.jpeg;\r\n modification-date=\"Wed, 12 Feb 1997 16:29:51 -0500\"\r\n").unwrap().1);
         assert_eq!(
             Disposition {
                 disposition_type: DispositionType::Attachment,
@@ -470,7 +471,7 @@ mod test {
                     .into_iter()
                     .collect()
             },
-            content_disposition(
+            content_disposition::<false>(
                 b"Content-Disposition: attachment; param*0=foo;\r\n param*1=bar\r\n"
             )
             .unwrap()
@@ -481,14 +482,14 @@ mod test {
     #[test]
     fn test_content_id() {
         assert_eq!(
-            content_id(b"Content-ID: <123456@mubelotix.dev>\r\n")
+            content_id::<false>(b"Content-ID: <123456@mubelotix.dev>\r\n")
                 .unwrap()
                 .1
                  .0,
             "123456"
         );
         assert_eq!(
-            content_id(b"cOntent-id: <qpfpsqfh@gmail.com>\r\n")
+            content_id::<false>(b"cOntent-id: <qpfpsqfh@gmail.com>\r\n")
                 .unwrap()
                 .1
                  .1,
@@ -499,7 +500,7 @@ mod test {
     #[test]
     fn test_content_description() {
         assert_eq!(
-            content_description(
+            content_description::<false>(
                 b"Content-Description:a picture of the Space Shuttle Endeavor.\r\n"
             )
             .unwrap()
@@ -507,7 +508,7 @@ mod test {
             "a picture of the Space Shuttle Endeavor."
         );
         assert_eq!(
-            content_description(b"Content-DeScription:Ferris the crab\r\n")
+            content_description::<false>(b"Content-DeScription:Ferris the crab\r\n")
                 .unwrap()
                 .1,
             "Ferris the crab"
@@ -516,16 +517,22 @@ mod test {
 
     #[test]
     fn test_mime_version() {
-        assert_eq!(mime_version(b"MIME-Version: 1.0\r\n").unwrap().1, (1, 0));
-        assert_eq!(mime_version(b"MIME-VersIon: 1.2\r\n").unwrap().1, (1, 2));
         assert_eq!(
-            mime_version(b"MIME-VersIon: (produced by MetaSend Vx.x) 2.0\r\n")
+            mime_version::<false>(b"MIME-Version: 1.0\r\n").unwrap().1,
+            (1, 0)
+        );
+        assert_eq!(
+            mime_version::<false>(b"MIME-VersIon: 1.2\r\n").unwrap().1,
+            (1, 2)
+        );
+        assert_eq!(
+            mime_version::<false>(b"MIME-VersIon: (produced by MetaSend Vx.x) 2.0\r\n")
                 .unwrap()
                 .1,
             (2, 0)
         );
         assert_eq!(
-            mime_version(b"MIME-VersIon: 214.25 (produced by MetaSend Vx.x)\r\n")
+            mime_version::<false>(b"MIME-VersIon: 214.25 (produced by MetaSend Vx.x)\r\n")
                 .unwrap()
                 .1,
             (214, 25)
@@ -535,28 +542,34 @@ mod test {
     #[test]
     fn test_content_type() {
         assert_eq!(
-            content_type(b"Content-type: tExt/plain\r\n").unwrap().1 .0,
+            content_type::<false>(b"Content-type: tExt/plain\r\n")
+                .unwrap()
+                .1
+                 .0,
             ContentType::Text
         );
         assert_eq!(
-            (ContentType::Message, "external-body".into(), vec![("access-type".into(), "URL".into()), ("url".into(), "ftp://cs.utk.edu/pub/moore/bulk-mailer/bulk-mailer.tar".into())].into_iter().collect()), content_type(b"Content-Type: message/external-body; access-type=URL;\r\n URL*0=\"ftp://\";\r\n URL*1=\"cs.utk.edu/pub/moore/bulk-mailer/bulk-mailer.tar\"\r\n").unwrap().1,
+            (ContentType::Message, "external-body".into(), vec![("access-type".into(), "URL".into()), ("url".into(), "ftp://cs.utk.edu/pub/moore/bulk-mailer/bulk-mailer.tar".into())].into_iter().collect()), content_type::<false>(b"Content-Type: message/external-body; access-type=URL;\r\n URL*0=\"ftp://\";\r\n URL*1=\"cs.utk.edu/pub/moore/bulk-mailer/bulk-mailer.tar\"\r\n").unwrap().1,
         );
         assert_eq!(
-            (ContentType::Application, "x-stuff".into(), vec![("title".into(), "This is even more ***fun*** isn\'t it!".into())].into_iter().collect()), content_type(b"Content-Type: application/x-stuff;\r\n title*0*=us-ascii'en'This%20is%20even%20more%20;\r\n title*1*=%2A%2A%2Afun%2A%2A%2A%20;\r\n title*2=\"isn't it!\"\r\n").unwrap().1,
+            (ContentType::Application, "x-stuff".into(), vec![Parameter { name: "title".into(), value: "This is even more ***fun*** isn\'t it!".into(), charset: Some("us-ascii".into()), language: Some("en".into()) }].into_iter().collect()), content_type::<false>(b"Content-Type: application/x-stuff;\r\n title*0*=us-ascii'en'This%20is%20even%20more%20;\r\n title*1*=%2A%2A%2Afun%2A%2A%2A%20;\r\n title*2=\"isn't it!\"\r\n").unwrap().1,
         );
         assert_eq!(
-            content_type(b"Content-type: text/plain\r\n").unwrap().1 .1,
+            content_type::<false>(b"Content-type: text/plain\r\n")
+                .unwrap()
+                .1
+                 .1,
             "plain"
         );
         assert_eq!(
-            content_type(b"Content-type: multIpart/unknown\r\n")
+            content_type::<false>(b"Content-type: multIpart/unknown\r\n")
                 .unwrap()
                 .1
                  .0,
             ContentType::Multipart
         );
         assert_eq!(
-            content_type(b"Content-Type: text/plain; chaRSet=\"iso-8859-1\"\r\n")
+            content_type::<false>(b"Content-Type: text/plain; chaRSet=\"iso-8859-1\"\r\n")
                 .unwrap()
                 .1
                  .2
@@ -565,7 +578,7 @@ mod test {
             "iso-8859-1"
         );
         assert_eq!(
-            content_type(b"Content-Type: text/plain; charset=\"iso-8859-1\"\r\n")
+            content_type::<false>(b"Content-Type: text/plain; charset=\"iso-8859-1\"\r\n")
                 .unwrap()
                 .1
                  .2
@@ -574,7 +587,7 @@ mod test {
             "iso-8859-1"
         );
         assert_eq!(
-            content_type(b"Content-type: text/plain; charset=us-ascii (Plain text)\r\n")
+            content_type::<false>(b"Content-type: text/plain; charset=us-ascii (Plain text)\r\n")
                 .unwrap()
                 .1
                  .2
@@ -583,7 +596,7 @@ mod test {
             "us-ascii"
         );
         assert_eq!(
-            content_type(b"Content-type: text/plain; charset=\"us-ascii\"\r\n")
+            content_type::<false>(b"Content-type: text/plain; charset=\"us-ascii\"\r\n")
                 .unwrap()
                 .1
                  .2
@@ -591,27 +604,29 @@ mod test {
                 .unwrap(),
             "us-ascii"
         );
-        assert_eq!(content_type(b"Content-Type: multipart/alternative; \r\n\tboundary=\"_000_DB6P193MB0021E64E5870F10170A32CB8EB920DB6P193MB0021EURP_\"\r\n").unwrap().1.2.get("boundary").unwrap(), "_000_DB6P193MB0021E64E5870F10170A32CB8EB920DB6P193MB0021EURP_");
+        assert_eq!(content_type::<false>(b"Content-Type: multipart/alternative; \r\n\tboundary=\"_000_DB6P193MB0021E64E5870F10170A32CB8EB920DB6P193MB0021EURP_\"\r\n").unwrap().1.2.get("boundary").unwrap(), "_000_DB6P193MB0021E64E5870F10170A32CB8EB920DB6P193MB0021EURP_");
     }
 
     #[test]
     fn test_content_transfer_encoding() {
         assert_eq!(
-            content_transfer_encoding(b"Content-Transfer-Encoding: 7bit\r\n")
+            content_transfer_encoding::<false>(b"Content-Transfer-Encoding: 7bit\r\n")
                 .unwrap()
                 .1,
             ContentTransferEncoding::SevenBit
         );
         assert_eq!(
-            content_transfer_encoding(b"Content-Transfer-Encoding: binary (invalid) \r\n")
+            content_transfer_encoding::<false>(b"Content-Transfer-Encoding: binary (invalid) \r\n")
                 .unwrap()
                 .1,
             ContentTransferEncoding::Binary
         );
         assert_eq!(
-            content_transfer_encoding(b"Content-Transfer-Encoding: (not readable) base64 \r\n")
-                .unwrap()
-                .1,
+            content_transfer_encoding::<false>(
+                b"Content-Transfer-Encoding: (not readable) base64 \r\n"
+            )
+            .unwrap()
+            .1,
             ContentTransferEncoding::Base64
         );
     }
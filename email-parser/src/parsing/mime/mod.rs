@@ -1,8 +1,17 @@
+pub(crate) mod attachments;
 pub(crate) mod base64;
+pub(crate) mod body;
 /// RFC 2047
 pub(crate) mod encoded_headers;
 pub(crate) mod entity;
+pub(crate) mod external_body;
+pub(crate) mod flowed;
+pub(crate) mod html;
 pub(crate) mod mime_fields;
 pub(crate) mod multipart;
+pub(crate) mod partial;
 pub(crate) mod percent_encoding;
 pub(crate) mod quoted_printables;
+pub(crate) mod related;
+pub(crate) mod sanitizer;
+pub(crate) mod segments;
@@ -1,6 +1,5 @@
 use crate::prelude::*;
 use std::borrow::Cow;
-use std::collections::HashMap;
 
 fn before_boundary_idx(input: &[u8], boundary: &[u8]) -> Result<(usize, usize), Error> {
     let full_boundary_len = 2 + 2 + boundary.len() + 2;
@@ -76,7 +75,7 @@ fn before_closing_boundary<'a, 'b>(input: &'a [u8], boundary: &'b [u8]) -> Res<'
 
 pub fn parse_multipart<'a>(
     input: &'a [u8],
-    parameters: &HashMap<Cow<str>, Cow<str>>,
+    parameters: &Parameters,
 ) -> Result<Vec<RawEntity<'a>>, Error> {
     let boundary = parameters
         .get("boundary")
@@ -94,6 +93,17 @@ pub fn parse_multipart<'a>(
     Ok(entities)
 }
 
+/// Splits a multipart entity into its parts, taking ownership of the data when needed.
+pub fn parts(entity: RawEntity) -> Result<Vec<RawEntity>, Error> {
+    match entity.value {
+        Cow::Borrowed(value) => parse_multipart(value, &entity.parameters),
+        Cow::Owned(value) => Ok(parse_multipart(&value, &entity.parameters)?
+            .into_iter()
+            .map(|part| part.into_owned())
+            .collect()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
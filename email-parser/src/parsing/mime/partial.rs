@@ -0,0 +1,122 @@
+use crate::parsing::fields::header_fields;
+
+/// Fields that are taken from the encapsulated message rather than from the enclosing one.\
+/// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.2.1)
+fn is_enclosed_field(name: &[u8]) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with(b"content-")
+        || name == b"subject"
+        || name == b"message-id"
+        || name == b"encrypted"
+        || name == b"mime-version"
+}
+
+/// Splits the value of the first fragment into the header section of the encapsulated message and the beginning of its body.
+pub fn split_enclosed(value: &[u8]) -> (&[u8], &[u8]) {
+    if let Some(body) = value.strip_prefix(b"\r\n") {
+        return (&[], body);
+    }
+
+    for idx in 0..value.len() {
+        if value.get(idx..idx + 4) == Some(b"\r\n\r\n") {
+            return (&value[..idx + 2], &value[idx + 4..]);
+        }
+    }
+
+    (value, &[])
+}
+
+/// Builds the header section of a reassembled message from the header of the first enclosing message and the header of the encapsulated message.\
+/// [Learn more](https://tools.ietf.org/html/rfc2046#section-5.2.2.1)
+pub fn merge_headers(enclosing: &[u8], enclosed: &[u8]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(enclosing.len() + enclosed.len());
+
+    for (name, field) in header_fields(enclosing) {
+        if !is_enclosed_field(name) {
+            header.extend_from_slice(field);
+        }
+    }
+    for (name, field) in header_fields(enclosed) {
+        if is_enclosed_field(name) {
+            header.extend_from_slice(field);
+        }
+    }
+
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_merge_headers() {
+        assert_eq!(
+            merge_headers(
+                b"From: a@b.c\r\nSubject: Part 1 of 2\r\nMessage-ID: <1@b.c>\r\nMIME-Version: 1.0\r\nContent-Type: message/partial; id=\"abc@b.c\";\r\n number=1; total=2\r\n",
+                b"Date: 5 May 2003 18:58:34 +0000\r\nSubject: Audio mail\r\nMessage-ID: <2@b.c>\r\nContent-Type: audio/basic\r\n"
+            ),
+            b"From: a@b.c\r\nSubject: Audio mail\r\nMessage-ID: <2@b.c>\r\nContent-Type: audio/basic\r\n".to_vec()
+        );
+
+        assert_eq!(
+            split_enclosed(b"Subject: Test\r\n\r\nBody"),
+            (&b"Subject: Test\r\n"[..], &b"Body"[..])
+        );
+        assert_eq!(split_enclosed(b"\r\nBody"), (&b""[..], &b"Body"[..]));
+    }
+
+    #[test]
+    fn test_reassembly() {
+        let mut reassembler = PartialReassembler::new();
+
+        let fragment = b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nSubject: Audio mail (part 2 of 2)\r\nMIME-Version: 1.0\r\nContent-Type: Message/Partial; number=2; total=2;\r\n id=\"ABC@host.com\"\r\n\r\nend of the body.\r\n";
+        let fragment = Email::parse(fragment).unwrap();
+        assert!(reassembler.add(&fragment).unwrap().is_none());
+
+        let fragment = b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nSubject: Audio mail (part 1 of 2)\r\nMessage-ID: <id1@host.com>\r\nMIME-Version: 1.0\r\nContent-Type: message/partial; id=\"ABC@host.com\";\r\n number=1; total=2\r\n\r\nSubject: Audio mail\r\nMessage-ID: <id2@host.com>\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=us-ascii\r\n\r\nThis is the beginning and the ";
+        let fragment = Email::parse(fragment).unwrap();
+        let message = reassembler.add(&fragment).unwrap().unwrap();
+
+        let email = Email::parse(&message).unwrap();
+        assert_eq!(email.subject.unwrap(), " Audio mail");
+        assert_eq!(email.message_id.unwrap().0, "id2");
+        assert_eq!(email.sender.address.local_part, "mubelotix");
+        assert_eq!(
+            Entity::Text {
+                subtype: &"plain".into(),
+                value: "This is the beginning and the end of the body.\r\n".into()
+            },
+            email.mime_entity.parse().unwrap()
+        );
+
+        let fragment = |number: usize, total: &str| {
+            format!("From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: message/partial; id=\"DEF@host.com\"; number={}{}\r\n\r\nBody\r\n", number, total)
+        };
+        let mut reassembler = PartialReassembler::new();
+        let third = fragment(3, "");
+        assert!(reassembler
+            .add(&Email::parse(third.as_bytes()).unwrap())
+            .unwrap()
+            .is_none());
+        let second = fragment(2, "; total=2");
+        assert!(reassembler
+            .add(&Email::parse(second.as_bytes()).unwrap())
+            .is_err());
+        let first = fragment(1, "; total=3");
+        assert!(reassembler
+            .add(&Email::parse(first.as_bytes()).unwrap())
+            .unwrap()
+            .is_none());
+        let second = fragment(2, "; total=4");
+        assert!(reassembler
+            .add(&Email::parse(second.as_bytes()).unwrap())
+            .is_err());
+        let second = fragment(2, "");
+        assert!(reassembler
+            .add(&Email::parse(second.as_bytes()).unwrap())
+            .unwrap()
+            .is_some());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use std::{borrow::Cow, collections::HashMap};
+use std::borrow::Cow;
 
 pub fn decode_parameter(mut input: Vec<u8>, charset: Cow<str>) -> Result<String, Error> {
     if ![
@@ -95,90 +95,110 @@ pub fn decode_parameter(mut input: Vec<u8>, charset: Cow<str>) -> Result<String,
     Ok(text)
 }
 
+/// Returns a part of a string without copying it if it is borrowed.
+fn substring<'a>(value: &Cow<'a, str>, range: std::ops::Range<usize>) -> Cow<'a, str> {
+    match value {
+        Cow::Borrowed(value) => Cow::Borrowed(&value[range]),
+        Cow::Owned(value) => Cow::Owned(value[range].to_string()),
+    }
+}
+
+/// Splits the first section of an encoded parameter into its charset, its language and its value.\
+/// Returns `None` if the charset and the language are missing.\
+/// [Learn more](https://tools.ietf.org/html/rfc2231#section-4)
+fn split_extended_value<'a>(
+    value: &Cow<'a, str>,
+) -> Option<(Option<Cow<'a, str>>, Option<Cow<'a, str>>, Cow<'a, str>)> {
+    let charset_end = value.find('\'')?;
+    let language_end = charset_end + 1 + value[charset_end + 1..].find('\'')?;
+
+    let charset = Some(lowercase(substring(value, 0..charset_end))).filter(|c| !c.is_empty());
+    let language = Some(substring(value, charset_end + 1..language_end)).filter(|l| !l.is_empty());
+    Some((
+        charset,
+        language,
+        substring(value, language_end + 1..value.len()),
+    ))
+}
+
+/// Reassembles the sections of a parameter, sorted by index.\
+/// Sections are decoded with the charset of the first one. If it is missing, UTF-8 is assumed.
+fn assemble_sections<'a>(
+    name: Cow<'a, str>,
+    sections: Vec<(u8, bool, Cow<'a, str>)>,
+) -> Result<Parameter<'a>, Error> {
+    let mut charset = None;
+    let mut language = None;
+    let mut value = Cow::Borrowed("");
+
+    for (index, encoded, section) in sections {
+        let section = if encoded {
+            let data = match split_extended_value(&section).filter(|_| index == 0) {
+                Some((section_charset, section_language, data)) => {
+                    charset = section_charset;
+                    language = section_language;
+                    data
+                }
+                None => section,
+            };
+            Cow::Owned(decode_parameter(
+                data.into_owned().into_bytes(),
+                charset.clone().unwrap_or(Cow::Borrowed("utf-8")),
+            )?)
+        } else {
+            section
+        };
+        add_string(&mut value, section);
+    }
+
+    Ok(Parameter {
+        name,
+        value,
+        charset,
+        language,
+    })
+}
+
 pub fn collect_parameters<'a>(
     parameters_vec: Vec<(Cow<'a, str>, Option<u8>, bool, Cow<'a, str>)>,
-) -> Result<HashMap<Cow<'a, str>, Cow<'a, str>>, Error> {
-    let mut parameters: HashMap<_, _> = HashMap::new();
-    let mut complex_parameters: HashMap<_, HashMap<_, _>> = HashMap::new();
+) -> Result<Parameters<'a>, Error> {
+    // Names in the order of their first occurrence, with their simple value and their sections.
+    let mut collected: Vec<(
+        Cow<'a, str>,
+        Option<Cow<'a, str>>,
+        Vec<(u8, bool, Cow<'a, str>)>,
+    )> = Vec::new();
     for (name, index, encoded, value) in parameters_vec {
-        if let Some(index) = index {
-            if !complex_parameters.contains_key(&name) {
-                complex_parameters.insert(name.clone(), HashMap::new());
+        let idx = match collected.iter().position(|(n, _, _)| *n == name) {
+            Some(idx) => idx,
+            None => {
+                collected.push((name, None, Vec::new()));
+                collected.len() - 1
             }
-            complex_parameters
-                .get_mut(&name)
-                .unwrap()
-                .insert(index, (encoded, value));
-        } else {
-            parameters.insert(name, value);
+        };
+
+        // An encoded parameter without continuation is handled like the first section of a continued one.
+        match (index, encoded) {
+            (None, false) => collected[idx].1 = Some(value),
+            (index, encoded) => collected[idx].2.push((index.unwrap_or(0), encoded, value)),
         }
     }
-    for (name, values) in complex_parameters.iter_mut() {
-        if let Some((encoded, value)) = values.remove(&0) {
-            let (mut value, charset, _language) = if encoded {
-                match value {
-                    Cow::Borrowed(value) => {
-                        let (value, charset) = take_while(value.as_bytes(), |c| c != b'\'')?;
-                        let charset = lowercase(Cow::Borrowed(charset));
-                        let (value, _) = tag(
-                            value,
-                            b"'",
-                            "TAG ERROR: In a parameter list, a charset must be followed by a `'`.",
-                        )?;
-                        let (value, language) = take_while(value, |c| c != b'\'')?;
-                        let (value, _) = tag(
-                            value,
-                            b"'",
-                            "TAG ERROR: In a parameter list, a language must be followed by a `'`.",
-                        )?;
-                        (
-                            Cow::Owned(decode_parameter(value.to_vec(), charset.clone())?),
-                            Some(charset),
-                            Some(Cow::Borrowed(language)),
-                        )
-                    }
-                    Cow::Owned(value) => {
-                        let (value, charset) = take_while(value.as_bytes(), |c| c != b'\'')?;
-                        let charset = lowercase(Cow::Borrowed(charset));
-                        let (value, _) = tag(
-                            value,
-                            b"'",
-                            "TAG ERROR: In a parameter list, a charset must be followed by a `'`.",
-                        )?;
-                        let (value, language) = take_while(value, |c| c != b'\'')?;
-                        let (value, _) = tag(
-                            value,
-                            b"'",
-                            "TAG ERROR: In a parameter list, a language must be followed by a `'`.",
-                        )?;
-                        (
-                            Cow::Owned(decode_parameter(value.to_vec(), charset.clone())?),
-                            Some(Cow::Owned(charset.into_owned())),
-                            Some(Cow::Owned(language.to_owned())),
-                        )
-                    }
-                }
-            } else {
-                (value, None, None)
-            };
 
-            let mut idx = 1;
-            while let Some((encoded, new_value)) = values.remove(&idx) {
-                if encoded && charset.is_some() {
-                    add_string(
-                        &mut value,
-                        Cow::Owned(decode_parameter(
-                            new_value.into_owned().into_bytes(),
-                            charset.clone().unwrap(),
-                        )?),
-                    );
-                } else {
-                    add_string(&mut value, new_value);
-                }
-                idx += 1;
+    let mut parameters = Parameters::new();
+    for (name, value, mut sections) in collected {
+        match value {
+            Some(value) if sections.is_empty() => parameters.insert(Parameter {
+                name,
+                value,
+                charset: None,
+                language: None,
+            }),
+            _ => {
+                // Sections may be out of order or duplicated. Missing sections are skipped.
+                sections.sort_by_key(|(index, _, _)| *index);
+                sections.dedup_by_key(|(index, _, _)| *index);
+                parameters.insert(assemble_sections(name, sections)?);
             }
-
-            parameters.insert(Cow::Owned(name.clone().into_owned()), value);
         }
     }
     Ok(parameters)
@@ -199,4 +219,58 @@ mod tests {
             decode_parameter("%2A%2A%2Afun%2A%2A%2A%20".into(), "us-ascii".into()).unwrap()
         );
     }
+
+    #[test]
+    fn test_collect_parameters() {
+        assert_eq!(
+            collect_parameters(vec![(
+                "filename".into(),
+                None,
+                true,
+                "utf-8''%C3%A9t%C3%A9.txt".into()
+            )])
+            .unwrap()
+            .get("filename")
+            .unwrap(),
+            "été.txt"
+        );
+    }
+
+    #[test]
+    fn test_parameter_sections() {
+        let parameters = collect_parameters(vec![
+            ("title".into(), Some(2), false, "isn't it!".into()),
+            ("charset".into(), None, false, "us-ascii".into()),
+            (
+                "title".into(),
+                Some(0),
+                true,
+                "us-ascii'en-us'This%20is%20".into(),
+            ),
+            (
+                "title".into(),
+                Some(1),
+                true,
+                "%2A%2A%2Afun%2A%2A%2A%20".into(),
+            ),
+            ("title".into(), None, false, "Fallback".into()),
+        ])
+        .unwrap();
+        let names: Vec<&str> = parameters.iter().map(|p| p.name.as_ref()).collect();
+        assert_eq!(names, vec!["title", "charset"]);
+        let title = parameters.get_parameter("TITLE").unwrap();
+        assert_eq!(title.value, "This is ***fun*** isn't it!");
+        assert_eq!(title.charset.as_ref().unwrap(), "us-ascii");
+        assert_eq!(parameters.language("title").unwrap(), "en-us");
+        assert!(parameters.language("charset").is_none());
+
+        let parameters = collect_parameters(vec![
+            ("name".into(), Some(0), false, "first-".into()),
+            ("name".into(), Some(3), false, "fourth".into()),
+            ("other".into(), Some(1), true, "%41".into()),
+        ])
+        .unwrap();
+        assert_eq!(parameters.get("name").unwrap(), "first-fourth");
+        assert_eq!(parameters.get("other").unwrap(), "A");
+    }
 }
@@ -0,0 +1,306 @@
+use crate::prelude::*;
+
+/// Attributes whose value is a URL that may reference another part of a `multipart/related` entity.
+const URL_ATTRIBUTES: [&str; 5] = ["src", "href", "background", "poster", "data"];
+
+/// Returns the value of the Content-Location header of an entity, without folding whitespaces.\
+/// [Learn more](https://tools.ietf.org/html/rfc2557#section-4.2)
+pub fn content_location(entity: &RawEntity) -> Option<String> {
+    entity
+        .additional_headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("Content-Location"))
+        .map(|(_, value)| value.split_whitespace().collect())
+}
+
+/// Finds the root part of a `multipart/related` entity.\
+/// It is the part whose Content-ID is the `start` parameter or, when there is no such parameter, the first part of the type specified by the `type` parameter.
+/// Falls back to the first part.\
+/// [Learn more](https://tools.ietf.org/html/rfc2387#section-3.1)
+pub fn root_index(parts: &[RawEntity], start: Option<&str>, root_type: Option<&str>) -> usize {
+    if let Some(start) = start {
+        let start = start.trim();
+        if let Some(idx) = parts
+            .iter()
+            .position(|part| super::body::content_id(part).as_deref() == Some(start))
+        {
+            return idx;
+        }
+    }
+
+    if let Some(root_type) = root_type {
+        let root_type = root_type.trim().to_ascii_lowercase();
+        if let Some(idx) = parts
+            .iter()
+            .position(|part| mime_type_string(part) == root_type)
+        {
+            return idx;
+        }
+    }
+
+    0
+}
+
+/// Returns the `type/subtype` string of an entity, in lowercase.
+pub fn mime_type_string(entity: &RawEntity) -> String {
+    let mime_type = match &entity.mime_type {
+        ContentType::Text => "text",
+        ContentType::Image => "image",
+        ContentType::Audio => "audio",
+        ContentType::Video => "video",
+        ContentType::Application => "application",
+        ContentType::Message => "message",
+        ContentType::Multipart => "multipart",
+        ContentType::Unknown(mime_type) => mime_type.as_ref(),
+    };
+    format!("{}/{}", mime_type, entity.subtype)
+}
+
+/// Decodes the `%hh` escapes of a URL.
+fn percent_decode(url: &str) -> String {
+    let mut bytes = Vec::with_capacity(url.len());
+    let mut idx = 0;
+    while idx < url.len() {
+        let byte = url.as_bytes()[idx];
+        if byte == b'%' {
+            if let Some(Ok(n)) = url.get(idx + 1..idx + 3).map(|h| u8::from_str_radix(h, 16)) {
+                bytes.push(n);
+                idx += 3;
+                continue;
+            }
+        }
+        bytes.push(byte);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Resolves a relative URL against the location of the root part.
+fn absolute_url(base: Option<&str>, url: &str) -> Option<String> {
+    let base = base?;
+    if url.contains(':') || url.is_empty() {
+        return None;
+    }
+
+    let scheme_end = base.find("://").map(|idx| idx + 3)?;
+    if url.starts_with('/') {
+        let authority_end = base[scheme_end..]
+            .find('/')
+            .map(|idx| scheme_end + idx)
+            .unwrap_or_else(|| base.len());
+        Some(format!("{}{}", &base[..authority_end], url))
+    } else {
+        let directory_end = base[scheme_end..]
+            .rfind('/')
+            .map(|idx| scheme_end + idx + 1);
+        match directory_end {
+            Some(end) => Some(format!("{}{}", &base[..end], url)),
+            None => Some(format!("{}/{}", base, url)),
+        }
+    }
+}
+
+/// Finds the part referenced by a `cid:` URL or by its Content-Location.\
+/// [Learn more](https://tools.ietf.org/html/rfc2392)
+pub fn resolve(parts: &[RawEntity], base: Option<&str>, url: &str) -> Option<usize> {
+    let url = url.trim();
+
+    if url.len() > 4 && url[..4].eq_ignore_ascii_case("cid:") {
+        let id = format!("<{}>", percent_decode(&url[4..]));
+        return parts
+            .iter()
+            .position(|part| super::body::content_id(part).as_deref() == Some(id.as_str()));
+    }
+
+    let absolute = absolute_url(base, url);
+    parts.iter().position(|part| match content_location(part) {
+        Some(location) => location == url || Some(&location) == absolute.as_ref(),
+        None => false,
+    })
+}
+
+/// Builds a `data:` URI containing the value of an entity.
+pub fn data_uri(entity: &RawEntity) -> String {
+    let mut data = super::base64::encode_base64(entity.value.to_vec());
+    data.retain(|c| *c != b'\r' && *c != b'\n');
+
+    // JUSTIFICATION
+    //  Benefit
+    //      Avoid checking the output of the base64 encoder.
+    //  Correctness
+    //      Base64 is valid ASCII so it cannot be invalid utf8.
+    let data = unsafe { String::from_utf8_unchecked(data) };
+    format!("data:{};base64,{}", mime_type_string(entity), data)
+}
+
+/// Replaces the URLs contained in the attributes of HTML tags.\
+/// The closure receives decoded URLs and returns the replacement URL, if any.
+pub fn rewrite_html<F>(html: &str, mut replace: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let bytes = html.as_bytes();
+    let is_space = |c: u8| c.is_ascii_whitespace();
+    let mut output = String::with_capacity(html.len());
+    let mut copied = 0;
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if bytes[idx] != b'<'
+            || !bytes
+                .get(idx + 1)
+                .map(u8::is_ascii_alphabetic)
+                .unwrap_or(false)
+        {
+            idx += 1;
+            continue;
+        }
+
+        // tag name
+        while idx < bytes.len() && !is_space(bytes[idx]) && bytes[idx] != b'>' {
+            idx += 1;
+        }
+
+        // attributes
+        loop {
+            while idx < bytes.len() && (is_space(bytes[idx]) || bytes[idx] == b'/') {
+                idx += 1;
+            }
+            if idx >= bytes.len() || bytes[idx] == b'>' {
+                break;
+            }
+
+            let name_start = idx;
+            while idx < bytes.len() && !is_space(bytes[idx]) && !b"=>/".contains(&bytes[idx]) {
+                idx += 1;
+            }
+            let name = html[name_start..idx].to_ascii_lowercase();
+
+            while idx < bytes.len() && is_space(bytes[idx]) {
+                idx += 1;
+            }
+            if bytes.get(idx) != Some(&b'=') {
+                continue;
+            }
+            idx += 1;
+            while idx < bytes.len() && is_space(bytes[idx]) {
+                idx += 1;
+            }
+
+            let raw_start = idx;
+            let (value_start, value_end) = match bytes.get(idx) {
+                Some(quote) if *quote == b'"' || *quote == b'\'' => {
+                    let start = idx + 1;
+                    let end = html[start..]
+                        .find(*quote as char)
+                        .map(|end| start + end)
+                        .unwrap_or_else(|| bytes.len());
+                    idx = (end + 1).min(bytes.len());
+                    (start, end)
+                }
+                _ => {
+                    let start = idx;
+                    while idx < bytes.len() && !is_space(bytes[idx]) && bytes[idx] != b'>' {
+                        idx += 1;
+                    }
+                    (start, idx)
+                }
+            };
+
+            if URL_ATTRIBUTES.contains(&name.as_str()) {
+                let value = super::html::decode_entities(&html[value_start..value_end]);
+                if let Some(url) = replace(&value) {
+                    // The value is always rewritten between double quotes, whatever the original quoting.
+                    output.push_str(&html[copied..raw_start]);
+                    output.push('"');
+                    output.push_str(&super::sanitizer::escape_attribute(&url));
+                    output.push('"');
+                    copied = idx;
+                }
+            }
+        }
+    }
+    output.push_str(&html[copied..]);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_url() {
+        let base = Some("http://www.example.com/images/page.html");
+        assert_eq!(
+            absolute_url(base, "logo.png").unwrap(),
+            "http://www.example.com/images/logo.png"
+        );
+        assert_eq!(
+            absolute_url(base, "/logo.png").unwrap(),
+            "http://www.example.com/logo.png"
+        );
+        assert!(absolute_url(base, "cid:logo").is_none());
+        assert!(absolute_url(None, "logo.png").is_none());
+    }
+
+    #[test]
+    fn test_rewrite_html() {
+        let html = "<p class=x>Text</p><IMG alt='a' SRC = \"one.png\"><a href=two?a=1&amp;b=2 target=_blank>link</a> <img src>";
+        let rewritten = rewrite_html(html, |url| match url {
+            "one.png" => Some(String::from("1")),
+            "two?a=1&b=2" => Some(String::from("2&\"")),
+            _ => None,
+        });
+        assert_eq!(
+            rewritten,
+            "<p class=x>Text</p><IMG alt='a' SRC = \"1\"><a href=\"2&amp;&quot;\" target=_blank>link</a> <img src>"
+        );
+
+        let rewritten = rewrite_html("<img src=a><img src='b'>", |url| match url {
+            "a" => Some(String::from("x onerror=alert(1)")),
+            _ => Some(String::from("'><script>")),
+        });
+        assert_eq!(
+            rewritten,
+            "<img src=\"x onerror=alert(1)\"><img src=\"'&gt;&lt;script&gt;\">"
+        );
+    }
+
+    #[test]
+    fn test_related_resolver() {
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: multipart/related; boundary=related; type=\"text/html\"\r\n\r\n--related\r\nContent-Type: text/plain\r\n\r\nNot the root\r\n--related\r\nContent-Type: text/html\r\nContent-Location: http://www.example.com/page.html\r\n\r\n<img src=\"cid:logo%40mubelotix.dev\"><img src=\"images/photo.jpg\"><img src=\"cid:missing\">\r\n--related\r\nContent-Type: image/png\r\nContent-ID: <logo@mubelotix.dev>\r\nContent-Transfer-Encoding: base64\r\n\r\naGVsbG8=\r\n--related\r\nContent-Type: image/jpeg\r\nContent-Location: http://www.example.com/images/\r\n photo.jpg\r\n\r\nJPEG\r\n--related--\r\n").unwrap();
+        let resolver = RelatedResolver::new(&email.mime_entity).unwrap();
+        assert_eq!(resolver.parts().len(), 4);
+        assert_eq!(resolver.root().subtype, "html");
+        assert_eq!(
+            resolver.resolve("cid:logo@mubelotix.dev").unwrap().subtype,
+            "png"
+        );
+        assert_eq!(
+            resolver
+                .resolve("http://www.example.com/images/photo.jpg")
+                .unwrap()
+                .subtype,
+            "jpeg"
+        );
+        assert!(resolver.resolve("cid:missing").is_none());
+
+        let html = String::from_utf8_lossy(&resolver.root().value).into_owned();
+        assert_eq!(
+            resolver.rewrite_html_with_data_uris(&html),
+            "<img src=\"data:image/png;base64,aGVsbG8=\"><img src=\"data:image/jpeg;base64,SlBFRw==\"><img src=\"cid:missing\">"
+        );
+        assert_eq!(
+            resolver.rewrite_html(&html, |part| Some(format!("/attachments/{}", part.subtype))),
+            "<img src=\"/attachments/png\"><img src=\"/attachments/jpeg\"><img src=\"cid:missing\">"
+        );
+
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\nContent-Type: multipart/related; boundary=related; start=\"<root@mubelotix.dev>\"\r\n\r\n--related\r\nContent-Type: image/png\r\n\r\nPNG\r\n--related\r\nContent-Type: text/html\r\nContent-ID: <root@mubelotix.dev>\r\n\r\n<p>Root</p>\r\n--related--\r\n").unwrap();
+        let resolver = RelatedResolver::new(&email.mime_entity).unwrap();
+        assert_eq!(resolver.root().subtype, "html");
+
+        let email = Email::parse(b"From: Mubelotix <mubelotix@mubelotix.dev>\r\nDate: 5 May 2003 18:58:34 +0000\r\n\r\nHey!\r\n").unwrap();
+        assert!(RelatedResolver::new(&email.mime_entity).is_err());
+    }
+}
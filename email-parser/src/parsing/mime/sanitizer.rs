@@ -0,0 +1,277 @@
+use crate::mime::{HtmlSanitizer, SanitizeReport};
+
+/// Elements that are removed along with their content.
+const DROPPED_ELEMENTS: [&str; 13] = [
+    "script", "style", "title", "template", "iframe", "frame", "frameset", "object", "embed",
+    "applet", "form", "select", "textarea",
+];
+
+/// Elements related to forms.
+const FORM_ELEMENTS: [&str; 8] = [
+    "form", "input", "button", "select", "textarea", "option", "label", "fieldset",
+];
+
+/// Elements embedding other documents.
+const FRAME_ELEMENTS: [&str; 6] = ["iframe", "frame", "frameset", "object", "embed", "applet"];
+
+/// Attributes containing a URL.
+const URL_ATTRIBUTES: [&str; 6] = ["href", "src", "background", "poster", "cite", "action"];
+
+/// Attributes whose URL is loaded automatically when the HTML is displayed.
+const RESOURCE_ATTRIBUTES: [&str; 3] = ["src", "background", "poster"];
+
+/// Returns the position of the `>` ending a tag, ignoring the ones in quoted attribute values.
+fn tag_end(input: &str) -> Option<usize> {
+    let bytes = input.as_bytes();
+    let mut last = 0;
+    let mut idx = 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'>' => return Some(idx),
+            quote @ b'"' | quote @ b'\'' if last == b'=' => {
+                idx += input[idx + 1..].find(quote as char)? + 1;
+            }
+            _ => (),
+        }
+        if !bytes[idx].is_ascii_whitespace() {
+            last = bytes[idx];
+        }
+        idx += 1;
+    }
+    None
+}
+
+/// Returns the lowercase scheme of a URL, ignoring the whitespaces and control characters browsers ignore.
+fn scheme(url: &str) -> Option<String> {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect();
+    let end = url.find([':', '/', '?', '#'])?;
+    if url[end..].starts_with(':') {
+        Some(url[..end].to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+/// Escapes a value so that it can be put between double quotes.
+pub fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Rebuilds an allowed tag, keeping only the safe and allowed attributes.
+fn sanitize_tag(
+    tag: &str,
+    name: &str,
+    sanitizer: &HtmlSanitizer,
+    report: &mut SanitizeReport,
+) -> String {
+    let mut output = format!("<{}", name);
+
+    for (attribute, value) in super::html::attributes(tag) {
+        if attribute.starts_with("on") {
+            report.event_handlers += 1;
+            continue;
+        }
+        if !sanitizer.allowed_attributes.contains(&attribute) {
+            report.disallowed_attributes += 1;
+            continue;
+        }
+
+        if URL_ATTRIBUTES.contains(&attribute.as_str()) {
+            let trimmed = value.trim();
+            let scheme = scheme(trimmed);
+            let is_image_data = scheme.as_deref() == Some("data")
+                && attribute == "src"
+                && trimmed
+                    .split_once(':')
+                    .map(|(_, data)| data.trim_start().to_ascii_lowercase().starts_with("image/"))
+                    .unwrap_or(false);
+
+            if let Some(scheme) = &scheme {
+                if !sanitizer.allowed_url_schemes.contains(scheme) && !is_image_data {
+                    report.unsafe_urls += 1;
+                    continue;
+                }
+            }
+
+            let is_remote = trimmed.starts_with("//")
+                || matches!(
+                    scheme.as_deref(),
+                    Some("http") | Some("https") | Some("ftp")
+                );
+            if is_remote
+                && !sanitizer.allow_remote_resources
+                && RESOURCE_ATTRIBUTES.contains(&attribute.as_str())
+            {
+                report.remote_resources += 1;
+                continue;
+            }
+        }
+
+        output.push(' ');
+        output.push_str(&attribute);
+        output.push_str("=\"");
+        output.push_str(&escape_attribute(&value));
+        output.push('"');
+    }
+
+    if tag.ends_with('/') {
+        output.push_str(" /");
+    }
+    output.push('>');
+    output
+}
+
+/// Removes the dangerous and disallowed parts of an HTML document.\
+/// Text is kept, except in scripts, styles, frames and forms.
+pub fn sanitize(html: &str, sanitizer: &HtmlSanitizer) -> (String, SanitizeReport) {
+    let mut output = String::with_capacity(html.len());
+    let mut report = SanitizeReport::default();
+    let mut rest = html;
+
+    while let Some(idx) = rest.find('<') {
+        output.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment
+                .find("-->")
+                .map(|end| &comment[end + 3..])
+                .unwrap_or("");
+            continue;
+        }
+
+        if !rest[1..]
+            .starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!' || c == '?')
+        {
+            output.push_str("&lt;");
+            rest = &rest[1..];
+            continue;
+        }
+
+        let end = match tag_end(rest) {
+            Some(end) => end,
+            None => {
+                rest = "";
+                break;
+            }
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        if !closing {
+            if name == "script" {
+                report.scripts += 1;
+            } else if FORM_ELEMENTS.contains(&name.as_str()) {
+                report.forms += 1;
+            } else if FRAME_ELEMENTS.contains(&name.as_str()) {
+                report.frames += 1;
+            } else if !sanitizer.allowed_tags.contains(&name) {
+                report.disallowed_tags += 1;
+            }
+        }
+
+        if DROPPED_ELEMENTS.contains(&name.as_str()) {
+            if !closing {
+                let closing = format!("</{}", name);
+                rest = match rest.to_ascii_lowercase().find(&closing) {
+                    Some(idx) => &rest[idx..],
+                    None => "",
+                };
+            }
+            continue;
+        }
+
+        if !sanitizer.allowed_tags.contains(&name)
+            || FORM_ELEMENTS.contains(&name.as_str())
+            || FRAME_ELEMENTS.contains(&name.as_str())
+        {
+            continue;
+        }
+
+        if closing {
+            output.push_str("</");
+            output.push_str(&name);
+            output.push('>');
+        } else {
+            output.push_str(&sanitize_tag(tag, &name, sanitizer, &mut report));
+        }
+    }
+    output.push_str(rest);
+
+    (output, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_end() {
+        assert_eq!(tag_end("<a href=\"x>y\">"), Some(13));
+        assert_eq!(tag_end("<p>"), Some(2));
+        assert_eq!(tag_end("<a title='>'"), None);
+        assert_eq!(tag_end("<b don't>"), Some(8));
+    }
+
+    #[test]
+    fn test_scheme() {
+        assert_eq!(scheme("https://example.com").unwrap(), "https");
+        assert_eq!(scheme(" Java\tScript:alert(1)").unwrap(), "javascript");
+        assert!(scheme("/images/a.png").is_none());
+        assert!(scheme("page?a=b:c").is_none());
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let sanitizer = HtmlSanitizer::default();
+
+        let (html, report) = sanitize("<html><head><title>T</title><script>alert(1)</script></head><body onload=\"track()\"><p class=x style=\"color: red\" onclick='evil()'>Hello <b>world</b></p><SCRIPT>alert(2)</SCRIPT><img src=\"https://tracker.example.com/pixel.gif\" width=1 height=1><img src=\"cid:logo@example.com\" alt=\"Logo &amp; co\"><a href=\" javascript:alert(3)\">click</a><a href=\"https://example.com/?a=1&amp;b=2\">link</a><form action=\"https://example.com\"><input name=password></form><iframe src=\"https://example.com\"></iframe><!-- comment -->1 < 2</body></html>", &sanitizer);
+        assert_eq!(html, "<p>Hello <b>world</b></p><img width=\"1\" height=\"1\"><img src=\"cid:logo@example.com\" alt=\"Logo &amp; co\"><a>click</a><a href=\"https://example.com/?a=1&amp;b=2\">link</a>1 &lt; 2");
+        assert_eq!(
+            report,
+            SanitizeReport {
+                scripts: 2,
+                event_handlers: 1,
+                unsafe_urls: 1,
+                remote_resources: 1,
+                forms: 1,
+                frames: 1,
+                disallowed_tags: 4,
+                disallowed_attributes: 2,
+            }
+        );
+
+        let (html, report) = sanitize("<img src=\"data:image/png;base64,AAAA\"><a href=\"data:text/html,<script>\">x</a><br/>", &sanitizer);
+        assert_eq!(
+            html,
+            "<img src=\"data:image/png;base64,AAAA\"><a>x</a><br />"
+        );
+        assert_eq!(report.unsafe_urls, 1);
+
+        let sanitizer = HtmlSanitizer {
+            allow_remote_resources: true,
+            ..HtmlSanitizer::default()
+        };
+        let (html, report) = sanitize("<img src=\"//example.com/a.png\">", &sanitizer);
+        assert_eq!(html, "<img src=\"//example.com/a.png\">");
+        assert_eq!(report, SanitizeReport::default());
+    }
+}
@@ -0,0 +1,181 @@
+use crate::mime::{SegmentKind, TextSegment};
+
+/// The beginnings and verbs of the lines introducing a quote, such as `On Mon, 5 May 2003, Mubelotix wrote:`.
+const ATTRIBUTIONS: [(&str, &str); 9] = [
+    ("On ", "wrote"),
+    ("Le ", "a écrit"),
+    ("Am ", "schrieb"),
+    ("El ", "escribió"),
+    ("Il ", "ha scritto"),
+    ("Op ", "schreef"),
+    ("Em ", "escreveu"),
+    ("Den ", "skrev"),
+    ("W dniu ", "pisze"),
+];
+
+fn is_attribution(line: &str) -> bool {
+    let line = line.trim();
+    line.ends_with(':')
+        && ATTRIBUTIONS
+            .iter()
+            .any(|(start, verb)| line.starts_with(start) && line.contains(verb))
+}
+
+/// Detects the separators inserted by Outlook before the original message, such as `-----Original Message-----`.\
+/// Localized versions and the line of underscores followed by a `From:` field are supported too.
+fn is_original_message_separator(line: &str, next_line: Option<&str>) -> bool {
+    let line = line.trim();
+    if line.len() > 10
+        && line.starts_with("-----")
+        && line.ends_with("-----")
+        && line.trim_matches('-').contains(char::is_alphabetic)
+    {
+        return true;
+    }
+
+    line.len() >= 20
+        && line.chars().all(|c| c == '_')
+        && next_line
+            .and_then(|next_line| next_line.trim().split_once(':'))
+            .map(|(name, _)| !name.is_empty() && name.chars().all(char::is_alphabetic))
+            .unwrap_or(false)
+}
+
+/// Returns `true` if the line is the signature delimiter.\
+/// [Learn more](https://tools.ietf.org/html/rfc3676#section-4.3)
+fn is_signature_delimiter(line: &str) -> bool {
+    line.trim_end_matches(['\r', '\n']) == "-- "
+}
+
+/// Classifies each line of a text and merges consecutive lines of the same kind.\
+/// Blank lines belong to the segment preceding them.
+pub fn split(text: &str) -> Vec<TextSegment> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        lines.push((start, line));
+        start += line.len();
+    }
+
+    let mut kinds: Vec<SegmentKind> = Vec::with_capacity(lines.len());
+    let mut in_original_message = false;
+    let mut in_signature = false;
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx].1;
+        let next_line = lines.get(idx + 1).map(|(_, line)| *line);
+
+        if !in_original_message && is_original_message_separator(line, next_line) {
+            in_original_message = true;
+        }
+
+        // Attributions are sometimes wrapped in two lines.
+        let is_wrapped_attribution = !line.trim().is_empty()
+            && !line.trim().ends_with(':')
+            && next_line
+                .map(|next_line| is_attribution(&format!("{} {}", line.trim(), next_line.trim())))
+                .unwrap_or(false);
+
+        let kind = if in_original_message || line.starts_with('>') || is_attribution(line) {
+            SegmentKind::Quote
+        } else if is_wrapped_attribution {
+            kinds.push(SegmentKind::Quote);
+            idx += 1;
+            SegmentKind::Quote
+        } else if line.trim().is_empty() {
+            kinds.last().copied().unwrap_or(SegmentKind::Content)
+        } else if in_signature || is_signature_delimiter(line) {
+            in_signature = true;
+            SegmentKind::Signature
+        } else {
+            SegmentKind::Content
+        };
+
+        if kind == SegmentKind::Quote {
+            in_signature = false;
+        }
+        kinds.push(kind);
+        idx += 1;
+    }
+
+    let mut segments: Vec<TextSegment> = Vec::new();
+    for ((start, line), kind) in lines.into_iter().zip(kinds) {
+        match segments.last_mut() {
+            Some(segment) if segment.kind == kind => segment.range.end = start + line.len(),
+            _ => segments.push(TextSegment {
+                kind,
+                range: start..start + line.len(),
+            }),
+        }
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(text: &str) -> Vec<(SegmentKind, &str)> {
+        split(text)
+            .into_iter()
+            .map(|segment| (segment.kind, &text[segment.range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_bottom_posting() {
+        assert_eq!(
+            segments("Hi,\r\n\r\nOn Mon, 5 May 2003 at 18:58, Mubelotix <mubelotix@mubelotix.dev>\r\nwrote:\r\n> How are you?\r\n>\r\n> > Old text\r\n\r\nFine, thanks.\r\n\r\n-- \r\nMubelotix\r\nhttps://mubelotix.dev\r\n"),
+            vec![
+                (SegmentKind::Content, "Hi,\r\n\r\n"),
+                (SegmentKind::Quote, "On Mon, 5 May 2003 at 18:58, Mubelotix <mubelotix@mubelotix.dev>\r\nwrote:\r\n> How are you?\r\n>\r\n> > Old text\r\n\r\n"),
+                (SegmentKind::Content, "Fine, thanks.\r\n\r\n"),
+                (SegmentKind::Signature, "-- \r\nMubelotix\r\nhttps://mubelotix.dev\r\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_top_posting() {
+        assert_eq!(
+            segments("Sounds good.\n-- \nJohn\nLe lun. 5 mai 2003 à 18:58, Mubelotix a écrit :\n> Shall we meet?"),
+            vec![
+                (SegmentKind::Content, "Sounds good.\n"),
+                (SegmentKind::Signature, "-- \nJohn\n"),
+                (SegmentKind::Quote, "Le lun. 5 mai 2003 à 18:58, Mubelotix a écrit :\n> Shall we meet?"),
+            ]
+        );
+
+        assert_eq!(
+            segments("See below.\r\n\r\n-----Original Message-----\r\nFrom: Mubelotix\r\nSubject: Test\r\n\r\nOriginal text\r\n"),
+            vec![
+                (SegmentKind::Content, "See below.\r\n\r\n"),
+                (SegmentKind::Quote, "-----Original Message-----\r\nFrom: Mubelotix\r\nSubject: Test\r\n\r\nOriginal text\r\n"),
+            ]
+        );
+
+        assert_eq!(
+            segments("Ok\n________________________________\nVon: Mubelotix\nText\n"),
+            vec![
+                (SegmentKind::Content, "Ok\n"),
+                (
+                    SegmentKind::Quote,
+                    "________________________________\nVon: Mubelotix\nText\n"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_quote() {
+        assert_eq!(
+            segments("On Monday, we will meet.\nThe answer is:\n-----\n"),
+            vec![(
+                SegmentKind::Content,
+                "On Monday, we will meet.\nThe answer is:\n-----\n"
+            )]
+        );
+        assert!(segments("").is_empty());
+    }
+}
@@ -5,6 +5,7 @@ pub(crate) mod character_sets;
 pub(crate) mod combinators;
 pub(crate) mod common;
 pub(crate) mod fields;
+pub(crate) mod idna;
 pub(crate) mod message;
 #[cfg(feature = "mime")]
 pub(crate) mod mime;
@@ -1,7 +1,7 @@
 use crate::prelude::*;
 use std::borrow::Cow;
 
-pub fn quoted_pair(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
+pub fn quoted_pair<const UTF8: bool>(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
     let (input, ()) = tag(
         input,
         b"\\",
@@ -9,7 +9,10 @@ pub fn quoted_pair(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
     )?;
 
     if let Some(character) = input.get(0) {
-        if is_vchar(*character) || is_wsp(*character) {
+        if is_utf8_non_ascii::<UTF8>(*character) {
+            let (input, value) = take_while1(input, is_utf8_non_ascii::<UTF8>)?;
+            Ok((input, Cow::Borrowed(value)))
+        } else if is_vchar::<UTF8>(*character) || is_wsp(*character) {
             // index are already checked
             unsafe {
                 Ok((
@@ -27,13 +30,26 @@ pub fn quoted_pair(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
     }
 }
 
-pub fn quoted_string(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
-    let input = if let Ok((input, _cfws)) = cfws(input) {
+pub fn quoted_string<const UTF8: bool>(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
+    let input = if let Ok((input, _cfws)) = cfws::<UTF8>(input) {
         input
     } else {
         input
     };
 
+    let (input, output) = bare_quoted_string::<UTF8>(input)?;
+
+    let input = if let Ok((input, _cfws)) = cfws::<UTF8>(input) {
+        input
+    } else {
+        input
+    };
+
+    Ok((input, output))
+}
+
+/// Like [quoted_string], but the surrounding whitespaces and comments are not consumed.
+pub fn bare_quoted_string<const UTF8: bool>(input: &[u8]) -> Res<'_, Cow<'_, str>> {
     let mut input = if input.starts_with(b"\"") {
         &input[1..]
     } else {
@@ -51,10 +67,10 @@ pub fn quoted_string(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
             input
         };
 
-        let new_input = if let Ok((new_input, str)) = take_while1(new_input, is_qtext) {
+        let new_input = if let Ok((new_input, str)) = take_while1(new_input, is_qtext::<UTF8>) {
             add_str(&mut additionnal_output, str);
             new_input
-        } else if let Ok((new_input, str)) = quoted_pair(new_input) {
+        } else if let Ok((new_input, str)) = quoted_pair::<UTF8>(new_input) {
             add_string(&mut additionnal_output, str);
             new_input
         } else {
@@ -78,12 +94,6 @@ pub fn quoted_string(input: &[u8]) -> Result<(&[u8], Cow<str>), Error> {
         return Err(Error::Unknown("Quoted string must end with a dquote"));
     };
 
-    let input = if let Ok((input, _cfws)) = cfws(input) {
-        input
-    } else {
-        input
-    };
-
     Ok((input, output))
 }
 
@@ -93,38 +103,40 @@ mod test {
 
     #[test]
     fn test_quoted_pair() {
-        assert!(quoted_pair(b"\\rtest").is_ok());
-        assert!(quoted_pair(b"\\ test").is_ok());
+        assert!(quoted_pair::<false>(b"\\rtest").is_ok());
+        assert!(quoted_pair::<false>(b"\\ test").is_ok());
 
-        assert_eq!(quoted_pair(b"\\rtest").unwrap().1, "r");
-        assert_eq!(quoted_pair(b"\\ test").unwrap().1, " ");
+        assert_eq!(quoted_pair::<false>(b"\\rtest").unwrap().1, "r");
+        assert_eq!(quoted_pair::<false>(b"\\ test").unwrap().1, " ");
 
-        assert!(quoted_pair(b"\\").is_err());
-        assert!(quoted_pair(b"\\\0").is_err());
-        assert!(quoted_pair(b"test").is_err());
+        assert!(quoted_pair::<false>(b"\\").is_err());
+        assert!(quoted_pair::<false>(b"\\\0").is_err());
+        assert!(quoted_pair::<false>(b"test").is_err());
     }
 
     #[test]
     fn test_quoted_string() {
         assert_eq!(
-            quoted_string(b" \"This\\ is\\ a\\ test\"").unwrap().1,
+            quoted_string::<false>(b" \"This\\ is\\ a\\ test\"")
+                .unwrap()
+                .1,
             "This is a test"
         );
         assert_eq!(
-            quoted_string(b"\r\n  \"This\\ is\\ a\\ test\"  ")
+            quoted_string::<false>(b"\r\n  \"This\\ is\\ a\\ test\"  ")
                 .unwrap()
                 .1,
             "This is a test"
         );
 
         assert!(matches!(
-            quoted_string(b"\r\n  \"This\\ is\\ a\\ test\"  ")
+            quoted_string::<false>(b"\r\n  \"This\\ is\\ a\\ test\"  ")
                 .unwrap()
                 .1,
             Cow::Owned(_)
         ));
         assert!(matches!(
-            quoted_string(b"\r\n  \"hey\"  ").unwrap().1,
+            quoted_string::<false>(b"\r\n  \"hey\"  ").unwrap().1,
             Cow::Borrowed(_)
         ));
     }
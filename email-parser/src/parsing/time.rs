@@ -177,11 +177,11 @@ pub fn date(input: &[u8]) -> Res<Date> {
     Ok((input, Date { day, month, year }))
 }
 
-pub fn date_time(input: &[u8]) -> Res<DateTime> {
+pub fn date_time<const UTF8: bool>(input: &[u8]) -> Res<DateTime> {
     let (input, day) = optional(input, day_of_week);
     let (input, date) = date(input)?;
     let (input, time) = time(input)?;
-    let (input, _cfws) = optional(input, cfws);
+    let (input, _cfws) = optional(input, cfws::<UTF8>);
     Ok((
         input,
         DateTime {
@@ -192,6 +192,314 @@ pub fn date_time(input: &[u8]) -> Res<DateTime> {
     ))
 }
 
+/// Zone names seen in the wild and their offsets from UTC in minutes.\
+/// The first ones are the obsolete zones of RFC 5322.\
+/// [Learn more](https://tools.ietf.org/html/rfc5322#section-4.3)
+const NAMED_ZONES: [(&str, i64); 42] = [
+    ("UT", 0),
+    ("GMT", 0),
+    ("EST", -300),
+    ("EDT", -240),
+    ("CST", -360),
+    ("CDT", -300),
+    ("MST", -420),
+    ("MDT", -360),
+    ("PST", -480),
+    ("PDT", -420),
+    ("Z", 0),
+    ("UTC", 0),
+    ("WET", 0),
+    ("WEST", 60),
+    ("BST", 60),
+    ("IST", 330),
+    ("CET", 60),
+    ("CEST", 120),
+    ("MET", 60),
+    ("MEST", 120),
+    ("MEZ", 60),
+    ("MESZ", 120),
+    ("EET", 120),
+    ("EEST", 180),
+    ("MSK", 180),
+    ("HKT", 480),
+    ("SGT", 480),
+    ("AWST", 480),
+    ("JST", 540),
+    ("KST", 540),
+    ("ACST", 570),
+    ("ACDT", 630),
+    ("AEST", 600),
+    ("AEDT", 660),
+    ("NZST", 720),
+    ("NZDT", 780),
+    ("HST", -600),
+    ("AKST", -540),
+    ("AKDT", -480),
+    ("AST", -240),
+    ("ADT", -180),
+    ("NST", -210),
+];
+
+fn number(text: &str) -> Option<u32> {
+    if text.is_empty() || text.len() > 9 || !text.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+fn zone_from_minutes(minutes: i64) -> Option<Zone> {
+    Zone::from_offset_seconds(minutes * 60)
+}
+
+/// Parses a zone such as `+0200`, `-05:00`, `+02`, `Z`, `CEST` or `GMT+2`.
+fn lenient_zone(token: &str) -> Option<Zone> {
+    if let Some(&(_, offset)) = NAMED_ZONES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(token))
+    {
+        return zone_from_minutes(offset);
+    }
+
+    let (prefix, offset) = token.split_at(token.find(['+', '-'])?);
+    if !prefix.is_empty()
+        && !["GMT", "UTC", "UT"]
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(prefix))
+    {
+        return None;
+    }
+
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let digits = offset[1..].replace(':', "");
+    if !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (number(&digits)?, 0),
+        4 => (number(&digits[..2])?, number(&digits[2..])?),
+        _ => return None,
+    };
+    if minutes > 59 {
+        return None;
+    }
+    zone_from_minutes(sign * (hours as i64 * 60 + minutes as i64))
+}
+
+/// Parses a time such as `18:58`, `8:58:34`, `18:58:34.123` or `18:58:34+02:00`.
+fn lenient_time(token: &str) -> Option<(Time, Option<Zone>)> {
+    let (time, zone) = match token.find(['Z', 'z', '+', '-']) {
+        Some(idx) => (&token[..idx], Some(lenient_zone(&token[idx..])?)),
+        None => (token, None),
+    };
+
+    let mut parts = time.split(':');
+    let hour = number(parts.next()?)?;
+    let minute = number(parts.next()?)?;
+    let second = match parts.next() {
+        Some(second) => number(second.split('.').next()?)?,
+        None => 0,
+    };
+    if parts.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    Some((
+        Time {
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second as u8,
+        },
+        zone,
+    ))
+}
+
+/// Converts obsolete two and three-digit years.\
+/// [Learn more](https://tools.ietf.org/html/rfc5322#section-4.3)
+fn full_year(year: u32, digits: usize) -> usize {
+    match digits {
+        1 | 2 if year < 50 => 2000 + year as usize,
+        1..=3 => 1900 + year as usize,
+        _ => year as usize,
+    }
+}
+
+/// Returns how much a date parsed with the RFC 5322 syntax can be trusted, or `None` if it cannot be used.\
+/// The year is checked before any computation. A day name that does not match the date is kept but makes the date guessed.
+pub fn strict_confidence(date_time: &DateTime) -> Option<DateConfidence> {
+    if date_time.date.year > crate::time::MAX_YEAR {
+        return None;
+    }
+    if date_time.validate().is_ok() {
+        return Some(DateConfidence::Exact);
+    }
+    let without_day_name = DateTime {
+        day_name: None,
+        ..date_time.clone()
+    };
+    without_day_name
+        .validate()
+        .ok()
+        .map(|()| DateConfidence::Guessed)
+}
+
+/// Parses a date that does not follow RFC 5322, trying to understand the formats produced by broken mailers.\
+/// Comments are ignored. Missing zones are assumed to be UTC and missing times to be midnight.
+/// The confidence tells whether the date follows RFC 5322, uses a non-standard but unambiguous syntax or had to be guessed.
+pub fn lenient_date_time<const UTF8: bool>(input: &str) -> Option<(DateTime, DateConfidence)> {
+    if let Ok((rest, date_time)) = date_time::<UTF8>(input.as_bytes()) {
+        if rest.iter().all(u8::is_ascii_whitespace) {
+            if let Some(confidence) = strict_confidence(&date_time) {
+                return Some((date_time, confidence));
+            }
+        }
+    }
+
+    let mut text = String::with_capacity(input.len());
+    let mut depth = 0;
+    for c in input.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            c if depth == 0 => text.push(c),
+            _ => (),
+        }
+    }
+
+    let mut guessed = false;
+    let mut day_name_token = None;
+    let mut day = None;
+    let mut month_token = None;
+    let mut year = None;
+    let mut time_token = None;
+    let mut zone_token = None;
+
+    for token in text.split(|c: char| c.is_whitespace() || c == ',') {
+        if token.is_empty() {
+            continue;
+        }
+
+        // ISO 8601 dates, such as `2003-05-05T18:58:34Z`
+        let bytes = token.as_bytes();
+        if bytes.len() >= 10
+            && bytes[..10].is_ascii()
+            && bytes[4] == b'-'
+            && bytes[7] == b'-'
+            && year.is_none()
+        {
+            if let (Some(y), Some(m), Some(d)) = (
+                number(&token[..4]),
+                number(&token[5..7]).and_then(|m| Month::from_number(m as u8)),
+                number(&token[8..10]),
+            ) {
+                year = Some(y as usize);
+                month_token = Some(m);
+                day = Some(d as u8);
+                let rest = &token[10..];
+                if let Some(time) = rest.strip_prefix(['T', 't']) {
+                    match lenient_time(time) {
+                        Some((time, zone)) => {
+                            time_token = Some(time);
+                            zone_token = zone.or(zone_token);
+                        }
+                        None => guessed = true,
+                    }
+                } else if !rest.is_empty() {
+                    guessed = true;
+                }
+                continue;
+            }
+        }
+
+        let lowercase = token.to_ascii_lowercase();
+        if token.contains(':') && time_token.is_none() {
+            if let Some((time, zone)) = lenient_time(token) {
+                time_token = Some(time);
+                zone_token = zone.or(zone_token);
+                continue;
+            }
+        }
+        if day_name_token.is_none() && token.len() >= 3 {
+            if let Ok((rest, day_name)) = day_name(lowercase.as_bytes()) {
+                let rest = std::str::from_utf8(rest).unwrap_or_default();
+                if rest.is_empty()
+                    || rest == "."
+                    || ["day", "sday", "nesday", "rsday", "urday"].contains(&rest)
+                {
+                    day_name_token = Some(day_name);
+                    continue;
+                }
+            }
+        }
+        if month_token.is_none() && token.len() >= 3 {
+            if let Ok((rest, month)) = month(lowercase.as_bytes()) {
+                let rest = std::str::from_utf8(rest).unwrap_or_default();
+                let full_name = format!("{:?}", month).to_ascii_lowercase();
+                if rest.is_empty() || rest == "." || full_name[3..] == *rest {
+                    month_token = Some(month);
+                    continue;
+                }
+            }
+        }
+        if zone_token.is_none() && time_token.is_some() {
+            if let Some(zone) = lenient_zone(token) {
+                zone_token = Some(zone);
+                continue;
+            }
+        }
+        if let Some(value) = number(token) {
+            if token.len() <= 2 && day.is_none() && year.is_none() && value >= 1 && value <= 31 {
+                day = Some(value as u8);
+                continue;
+            } else if year.is_none() && day.is_some() {
+                year = Some(full_year(value, token.len()));
+                continue;
+            }
+        }
+
+        // Unknown zone names and other garbage
+        guessed = true;
+    }
+
+    let date = Date {
+        day: day?,
+        month: month_token?,
+        year: year?,
+    };
+    if !date.is_valid() {
+        return None;
+    }
+
+    let time = time_token.unwrap_or_else(|| {
+        guessed = true;
+        Time {
+            hour: 0,
+            minute: 0,
+            second: 0,
+        }
+    });
+    let zone = zone_token.unwrap_or_else(|| {
+        guessed = true;
+        Zone::UTC
+    });
+    if matches!(&day_name_token, Some(day_name) if *day_name != date.weekday()) {
+        guessed = true;
+    }
+
+    let confidence = if guessed {
+        DateConfidence::Guessed
+    } else {
+        DateConfidence::Tolerant
+    };
+    Some((
+        DateTime {
+            day_name: day_name_token,
+            date,
+            time: TimeWithZone { time, zone },
+        },
+        confidence,
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -243,7 +551,9 @@ mod test {
         );
 
         assert_eq!(
-            date_time(b"Mon, 12 Apr 2023 10:25:03 +0000").unwrap().1,
+            date_time::<false>(b"Mon, 12 Apr 2023 10:25:03 +0000")
+                .unwrap()
+                .1,
             DateTime {
                 day_name: Some(Day::Monday),
                 date: Date {
@@ -266,7 +576,7 @@ mod test {
             }
         );
         assert_eq!(
-            date_time(b"5 May 2003 18:59:03 +0000").unwrap().1,
+            date_time::<false>(b"5 May 2003 18:59:03 +0000").unwrap().1,
             DateTime {
                 day_name: None,
                 date: Date {
@@ -380,4 +690,55 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn test_lenient_date_time() {
+        let parse = |date: &str| lenient_date_time::<false>(date).unwrap();
+        assert!(lenient_date_time::<false>("2003-05-0éx 18:00 +0000").is_none());
+        assert!(lenient_zone("+aé1").is_none());
+        let expected = parse("Mon, 5 May 2003 18:58:34 +0000").0;
+        assert_eq!(
+            parse("Mon, 5 May 2003 18:58:34 +0000").1,
+            DateConfidence::Exact
+        );
+        assert_eq!(
+            parse("Mon, 5 May 2003 18:58:34 -0000 (UTC)").1,
+            DateConfidence::Exact
+        );
+
+        for date in [
+            "Mon, 5 May 2003 18:58:34 GMT",
+            "Monday, 5 May 2003 18:58:34 +0000",
+            "Mon, 5 May 03 18:58:34 +0000",
+            "Mon May 5 18:58:34 UTC 2003",
+            "2003-05-05T18:58:34Z",
+            "2003-05-05 20:58:34.512+02:00",
+            "Mon, 05 May 2003 20:58:34 CEST",
+            "Mon, 5 May 2003 20:58:34 GMT+0200",
+        ] {
+            let (date_time, confidence) = parse(date);
+            assert_eq!(date_time.timestamp(), expected.timestamp(), "{}", date);
+            assert_eq!(confidence, DateConfidence::Tolerant, "{}", date);
+        }
+
+        for date in [
+            "Mon, 5 May 2003 18:58:34",
+            "Mon, 5 May 2003 18:58:34 +0000 garbage",
+            "Tue, 5 May 2003 18:58:34 +0000",
+            "Mon, 5 May 2003 18:58:34 XYZT",
+        ] {
+            let (date_time, confidence) = parse(date);
+            assert_eq!(date_time.timestamp(), expected.timestamp(), "{}", date);
+            assert_eq!(confidence, DateConfidence::Guessed, "{}", date);
+        }
+
+        let (date_time, _confidence) = parse("Tue, 5 May 2003 18:58:34 +0000");
+        assert_eq!(date_time.day_name, Some(Day::Tuesday));
+        let (date_time, _confidence) = parse("Tue, 5 May 2003 18:58:34 +0000 garbage");
+        assert_eq!(date_time.day_name, Some(Day::Tuesday));
+
+        assert!(lenient_date_time::<false>("30 Feb 2003 18:58:34 +0000").is_none());
+        assert!(lenient_date_time::<false>("1 Jan 99999999999999999 00:00 +0000").is_none());
+        assert!(lenient_date_time::<false>("yesterday").is_none());
+    }
 }
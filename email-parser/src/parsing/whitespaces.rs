@@ -28,26 +28,26 @@ pub fn fws(input: &[u8]) -> Res<Cow<str>> {
 }
 
 #[inline]
-pub fn ccontent(input: &[u8]) -> Res<Cow<str>> {
+pub fn ccontent<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
     match_parsers(
         input,
         &mut [
             (|i| {
-                let (input, value) = take_while1(i, is_ctext)?;
+                let (input, value) = take_while1(i, is_ctext::<UTF8>)?;
                 Ok((input, Cow::Borrowed(value)))
             }) as fn(input: &[u8]) -> Res<Cow<str>>,
-            quoted_pair,
-            comment,
+            quoted_pair::<UTF8>,
+            comment::<UTF8>,
         ][..],
     )
 }
 
 #[inline]
-pub fn comment(input: &[u8]) -> Res<Cow<str>> {
+pub fn comment<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
     let (input, ()) = tag(input, b"(", "TAG ERROR: A comment must start with a `(`.")?;
 
     let (input, _) = ignore_many(input, |input| {
-        pair(input, |i| Ok(optional(i, fws)), ccontent)
+        pair(input, |i| Ok(optional(i, fws)), ccontent::<UTF8>)
     })?;
 
     let (input, _) = optional(input, fws);
@@ -56,13 +56,92 @@ pub fn comment(input: &[u8]) -> Res<Cow<str>> {
     Ok((input, empty_string()))
 }
 
+/// Like [ccontent], but nested comments are returned with their content.
+fn ccontent_text<const UTF8: bool>(input: &[u8]) -> Res<'_, Cow<'_, str>> {
+    match_parsers(
+        input,
+        &mut [
+            (|i| {
+                let (input, value) = take_while1(i, is_ctext::<UTF8>)?;
+                Ok((input, Cow::Borrowed(value)))
+            }) as fn(input: &[u8]) -> Res<Cow<str>>,
+            quoted_pair::<UTF8>,
+            |i| {
+                let (input, comment) = comment_text::<UTF8>(i)?;
+                Ok((input, Cow::Owned(format!("({})", comment))))
+            },
+        ][..],
+    )
+}
+
+/// Parses a comment and returns its content, unfolded and without the surrounding parentheses.\
+/// Nested comments are kept with their parentheses.
+/// Unlike [comment], which is used on every token, this builds the content.
+fn comment_text<const UTF8: bool>(input: &[u8]) -> Res<'_, Cow<'_, str>> {
+    let (input, ()) = tag(input, b"(", "TAG ERROR: A comment must start with a `(`.")?;
+
+    let (input, content) = collect_many(input, |input| {
+        collect_pair(
+            input,
+            |i| Ok(fws(i).unwrap_or((i, empty_string()))),
+            ccontent_text::<UTF8>,
+        )
+    })?;
+
+    let (input, _) = optional(input, fws);
+    let (input, ()) = tag(input, b")", "TAG ERROR: A comment must end with a `)`.")?;
+
+    Ok((input, content))
+}
+
+/// Returns the comments contained in structured text that was already parsed.\
+/// Quoted strings and domain literals are skipped.
+pub fn comments<const UTF8: bool>(input: &[u8]) -> Vec<Cow<'_, str>> {
+    comments_and_trailing::<UTF8>(input).0
+}
+
+/// Like [comments], but also returns how many of the comments follow the last token of the text.
+pub fn comments_and_trailing<const UTF8: bool>(input: &[u8]) -> (Vec<Cow<'_, str>>, usize) {
+    let mut comments = Vec::new();
+    let mut first_trailing = 0;
+    let mut idx = 0;
+    while idx < input.len() {
+        match input[idx] {
+            b'(' => {
+                if let Ok((rest, comment)) = comment_text::<UTF8>(&input[idx..]) {
+                    comments.push(comment);
+                    idx = input.len() - rest.len();
+                    continue;
+                }
+                first_trailing = comments.len();
+            }
+            delimiter @ b'"' | delimiter @ b'[' => {
+                let end = if delimiter == b'"' { b'"' } else { b']' };
+                idx += 1;
+                while idx < input.len() && input[idx] != end {
+                    if input[idx] == b'\\' {
+                        idx += 1;
+                    }
+                    idx += 1;
+                }
+                first_trailing = comments.len();
+            }
+            b' ' | b'\t' | b'\r' | b'\n' => (),
+            _ => first_trailing = comments.len(),
+        }
+        idx += 1;
+    }
+    let trailing = comments.len() - first_trailing;
+    (comments, trailing)
+}
+
 #[inline]
-pub fn cfws(input: &[u8]) -> Res<Cow<str>> {
-    fn real_cfws(mut input: &[u8]) -> Res<Cow<str>> {
+pub fn cfws<const UTF8: bool>(input: &[u8]) -> Res<Cow<str>> {
+    fn real_cfws<const UTF8: bool>(mut input: &[u8]) -> Res<Cow<str>> {
         let mut output = empty_string();
 
         let (new_input, folding_wsp) = optional(input, fws);
-        if let Ok((new_input, _comment)) = comment(new_input) {
+        if let Ok((new_input, _comment)) = comment::<UTF8>(new_input) {
             input = new_input;
             if let Some(s) = folding_wsp {
                 add_string(&mut output, s);
@@ -74,7 +153,7 @@ pub fn cfws(input: &[u8]) -> Res<Cow<str>> {
         loop {
             let (new_input, folding_wsp) = optional(input, fws);
 
-            if let Ok((new_input, _comment)) = comment(new_input) {
+            if let Ok((new_input, _comment)) = comment::<UTF8>(new_input) {
                 input = new_input;
                 if let Some(s) = folding_wsp {
                     add_string(&mut output, s);
@@ -92,7 +171,7 @@ pub fn cfws(input: &[u8]) -> Res<Cow<str>> {
         Ok((input, output))
     }
 
-    match_parsers(input, &mut [real_cfws, fws][..])
+    match_parsers(input, &mut [real_cfws::<UTF8>, fws][..])
 }
 
 #[cfg(test)]
@@ -112,37 +191,68 @@ mod test {
 
     #[test]
     fn test_ccontent() {
-        assert_eq!(ccontent(b"abcde").unwrap().1, "abcde");
-        assert_eq!(ccontent(b"ab)cde").unwrap().1, "ab");
+        assert_eq!(ccontent::<false>(b"abcde").unwrap().1, "abcde");
+        assert_eq!(ccontent::<false>(b"ab)cde").unwrap().1, "ab");
     }
 
     #[test]
     fn test_comment() {
-        assert_eq!(comment(b"(this is a comment)").unwrap().0.len(), 0);
-        assert_eq!(comment(b"(a comment) and a value").unwrap().0.len(), 12);
+        assert_eq!(comment::<false>(b"(this is a comment)").unwrap().0.len(), 0);
         assert_eq!(
-            comment(b"(this is a comment (and another comment)) and a value")
+            comment::<false>(b"(a comment) and a value")
                 .unwrap()
                 .0
                 .len(),
             12
         );
+        assert_eq!(
+            comment::<false>(b"(this is a comment (and another comment)) and a value")
+                .unwrap()
+                .0
+                .len(),
+            12
+        );
+
+        assert_eq!(
+            comment_text::<false>(b"(this is\r\n a comment (and \\(another\\) comment))")
+                .unwrap()
+                .1,
+            "this is a comment (and (another) comment)"
+        );
 
-        assert!(comment(b"a value").is_err());
-        assert!(comment(b"(unclosed comment").is_err());
+        assert!(comment::<false>(b"a value").is_err());
+        assert!(comment::<false>(b"(unclosed comment").is_err());
+    }
+
+    #[test]
+    fn test_comments() {
+        assert_eq!(
+            comments::<false>(b"(a) \"not (a comment)\" [no (comment)] word (b (c))"),
+            vec!["a", "b (c)"]
+        );
+        assert!(comments::<false>(b"\"unclosed (").is_empty());
+
+        assert_eq!(
+            comments_and_trailing::<false>(b"(a) john(b)@example.com (c) (d)\r\n"),
+            (vec!["a".into(), "b".into(), "c".into(), "d".into()], 2)
+        );
+        assert_eq!(
+            comments_and_trailing::<false>(b"<john(work)@example.com>").1,
+            0
+        );
     }
 
     #[test]
     fn test_cfws() {
         assert_eq!(
-            cfws(b"  (this is a comment)\r\n (this is a second comment)  value")
+            cfws::<false>(b"  (this is a comment)\r\n (this is a second comment)  value")
                 .unwrap()
                 .1,
             "     "
         );
 
         assert_eq!(
-            cfws(b"  (this is a comment)\r\n (this is a second comment)\r\n  value")
+            cfws::<false>(b"  (this is a comment)\r\n (this is a second comment)\r\n  value")
                 .unwrap()
                 .1,
             "     "
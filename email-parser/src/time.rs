@@ -1,4 +1,8 @@
-#[derive(Debug, PartialEq, Clone)]
+use crate::error::Error;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Day {
     Monday,
     Tuesday,
@@ -9,7 +13,7 @@ pub enum Day {
     Sunday,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Month {
     January,
     February,
@@ -25,36 +29,605 @@ pub enum Month {
     December,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Zone {
     pub sign: bool,
     pub hour_offset: u8,
     pub minute_offset: u8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Time {
     pub hour: u8,
     pub minute: u8,
     pub second: u8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TimeWithZone {
     pub time: Time,
     pub zone: Zone,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Date {
     pub day: u8,
     pub month: Month,
     pub year: usize,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// How reliable a date is, depending on how far it is from the RFC 5322 syntax.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum DateConfidence {
+    /// Some parts of the date were guessed: the zone is unknown or missing, text was ignored or the day name was wrong.
+    Guessed,
+    /// The date does not follow RFC 5322 but its meaning is unambiguous, as with named zones, two-digit years or ISO 8601 dates.
+    Tolerant,
+    /// The date follows RFC 5322.
+    Exact,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct DateTime {
     pub day_name: Option<Day>,
     pub date: Date,
     pub time: TimeWithZone,
 }
+
+/// The largest year accepted by [DateTime::validate](DateTime::validate).
+pub const MAX_YEAR: usize = 9999;
+
+/// Returns the number of days between the Unix epoch and a date of the proleptic Gregorian calendar.\
+/// Computed on `i128` so that no year stored in a `usize` can overflow.
+fn days_from_civil(year: i128, month: i128, day: i128) -> i128 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the year, month and day of a number of days since the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// `usize::is_multiple_of` requires Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+fn is_leap_year(year: usize) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+impl Day {
+    fn from_days_since_epoch(days: i128) -> Day {
+        // 1 January 1970 was a Thursday.
+        match (days + 3).rem_euclid(7) {
+            0 => Day::Monday,
+            1 => Day::Tuesday,
+            2 => Day::Wednesday,
+            3 => Day::Thursday,
+            4 => Day::Friday,
+            5 => Day::Saturday,
+            _ => Day::Sunday,
+        }
+    }
+}
+
+impl Month {
+    /// Returns the number of the month, from 1 for January to 12 for December.
+    pub fn number(&self) -> u8 {
+        match self {
+            Month::January => 1,
+            Month::February => 2,
+            Month::March => 3,
+            Month::April => 4,
+            Month::May => 5,
+            Month::June => 6,
+            Month::July => 7,
+            Month::August => 8,
+            Month::September => 9,
+            Month::October => 10,
+            Month::November => 11,
+            Month::December => 12,
+        }
+    }
+
+    /// Returns the month corresponding to a number from 1 to 12.
+    pub fn from_number(number: u8) -> Option<Month> {
+        Some(match number {
+            1 => Month::January,
+            2 => Month::February,
+            3 => Month::March,
+            4 => Month::April,
+            5 => Month::May,
+            6 => Month::June,
+            7 => Month::July,
+            8 => Month::August,
+            9 => Month::September,
+            10 => Month::October,
+            11 => Month::November,
+            12 => Month::December,
+            _ => return None,
+        })
+    }
+}
+
+impl Zone {
+    /// The `+0000` zone.
+    pub const UTC: Zone = Zone {
+        sign: true,
+        hour_offset: 0,
+        minute_offset: 0,
+    };
+
+    /// Returns the offset from UTC in seconds.
+    pub fn offset_seconds(&self) -> i64 {
+        let offset = self.hour_offset as i64 * 3600 + self.minute_offset as i64 * 60;
+        if self.sign {
+            offset
+        } else {
+            -offset
+        }
+    }
+
+    /// Returns the zone corresponding to an offset from UTC in seconds, truncated to the minute.
+    pub(crate) fn from_offset_seconds(offset: i64) -> Option<Zone> {
+        let minutes = offset.abs() / 60;
+        if minutes / 60 > 99 {
+            return None;
+        }
+        Some(Zone {
+            sign: offset >= 0,
+            hour_offset: (minutes / 60) as u8,
+            minute_offset: (minutes % 60) as u8,
+        })
+    }
+}
+
+impl Date {
+    /// Returns the number of days in the month of this date.
+    pub fn days_in_month(&self) -> u8 {
+        match self.month {
+            Month::February if is_leap_year(self.year) => 29,
+            Month::February => 28,
+            Month::April | Month::June | Month::September | Month::November => 30,
+            _ => 31,
+        }
+    }
+
+    /// Returns `true` if this date exists in the Gregorian calendar (there is no 30 February).
+    pub fn is_valid(&self) -> bool {
+        self.day >= 1 && self.day <= self.days_in_month()
+    }
+
+    /// Returns the day of the week of this date.
+    pub fn weekday(&self) -> Day {
+        Day::from_days_since_epoch(self.days_since_epoch())
+    }
+
+    fn days_since_epoch(&self) -> i128 {
+        days_from_civil(
+            self.year as i128,
+            self.month.number() as i128,
+            self.day as i128,
+        )
+    }
+}
+
+impl DateTime {
+    /// Returns the number of seconds elapsed between the Unix epoch and this instant.\
+    /// Leap seconds are counted as the first second of the next minute.\
+    /// Saturates at `i64::MAX` for dates too far in the future, which [validate](DateTime::validate) rejects.
+    pub fn timestamp(&self) -> i64 {
+        i64::try_from(self.seconds_since_epoch()).unwrap_or(i64::MAX)
+    }
+
+    fn seconds_since_epoch(&self) -> i128 {
+        let time = &self.time.time;
+        self.date.days_since_epoch() * 86400
+            + time.hour as i128 * 3600
+            + time.minute as i128 * 60
+            + time.second as i128
+            - self.time.zone.offset_seconds() as i128
+    }
+
+    /// Creates the UTC date of a Unix timestamp.\
+    /// Returns `None` if the year would be negative.
+    pub fn from_timestamp(timestamp: i64) -> Option<DateTime> {
+        DateTime::from_local_timestamp(timestamp, Zone::UTC)
+    }
+
+    fn from_local_timestamp(timestamp: i64, zone: Zone) -> Option<DateTime> {
+        let local = timestamp.checked_add(zone.offset_seconds())?;
+        let days = local.div_euclid(86400);
+        let seconds = local.rem_euclid(86400);
+        let (year, month, day) = civil_from_days(days);
+        if year < 0 {
+            return None;
+        }
+
+        Some(DateTime {
+            day_name: Some(Day::from_days_since_epoch(days.into())),
+            date: Date {
+                day,
+                month: Month::from_number(month)?,
+                year: year as usize,
+            },
+            time: TimeWithZone {
+                time: Time {
+                    hour: (seconds / 3600) as u8,
+                    minute: (seconds % 3600 / 60) as u8,
+                    second: (seconds % 60) as u8,
+                },
+                zone,
+            },
+        })
+    }
+
+    /// Creates the date of a Unix timestamp, expressed in a zone.\
+    /// Returns `None` if the year would be negative.
+    pub fn from_timestamp_with_zone(timestamp: i64, zone: Zone) -> Option<DateTime> {
+        DateTime::from_local_timestamp(timestamp, zone)
+    }
+
+    /// Returns the same instant expressed in another zone.\
+    /// Returns `None` if the instant does not fit in a timestamp.
+    pub fn with_zone(&self, zone: Zone) -> Option<DateTime> {
+        let timestamp = i64::try_from(self.seconds_since_epoch()).ok()?;
+        DateTime::from_local_timestamp(timestamp, zone)
+    }
+
+    /// Returns the same instant expressed in UTC.
+    pub fn to_utc(&self) -> Option<DateTime> {
+        self.with_zone(Zone::UTC)
+    }
+
+    /// Checks that the year is at most [MAX_YEAR](MAX_YEAR), that the date exists, that the time is in range and that the day name, if any, is the day of the week of the date.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.date.year > MAX_YEAR {
+            return Err(Error::Unknown("Year out of range"));
+        }
+        if !self.date.is_valid() {
+            return Err(Error::Unknown("This day does not exist in this month"));
+        }
+        let time = &self.time.time;
+        if time.hour > 23 || time.minute > 59 || time.second > 60 {
+            return Err(Error::Unknown("Time out of range"));
+        }
+        if self.time.zone.minute_offset > 59 {
+            return Err(Error::Unknown("Zone minute offset out of range"));
+        }
+        match &self.day_name {
+            Some(day_name) if *day_name != self.date.weekday() => Err(Error::Unknown(
+                "The day name does not match the day of the week of the date",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// The fields of the date, used to order dates representing the same instant consistently with `Eq`.
+    fn fields(&self) -> (usize, u8, u8, u8, u8, u8, bool, u8, u8, Option<u8>) {
+        let time = &self.time.time;
+        (
+            self.date.year,
+            self.date.month.number(),
+            self.date.day,
+            time.hour,
+            time.minute,
+            time.second,
+            // `-0000` has the same offset as `+0000` but a different meaning.
+            self.time.zone.sign,
+            self.time.zone.hour_offset,
+            self.time.zone.minute_offset,
+            self.day_name.as_ref().map(|day| day.clone() as u8),
+        )
+    }
+}
+
+impl std::fmt::Display for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Day::Monday => "Mon",
+            Day::Tuesday => "Tue",
+            Day::Wednesday => "Wed",
+            Day::Thursday => "Thu",
+            Day::Friday => "Fri",
+            Day::Saturday => "Sat",
+            Day::Sunday => "Sun",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::fmt::Display for Month {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Month::January => "Jan",
+            Month::February => "Feb",
+            Month::March => "Mar",
+            Month::April => "Apr",
+            Month::May => "May",
+            Month::June => "Jun",
+            Month::July => "Jul",
+            Month::August => "Aug",
+            Month::September => "Sep",
+            Month::October => "Oct",
+            Month::November => "Nov",
+            Month::December => "Dec",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::fmt::Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{:02}{:02}",
+            if self.sign { '+' } else { '-' },
+            self.hour_offset,
+            self.minute_offset
+        )
+    }
+}
+
+impl std::fmt::Display for Time {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02} {} {:04}", self.day, self.month, self.year)
+    }
+}
+
+/// Formats the date as in the `Date` header, like `Tue, 05 May 2003 18:58:34 +0000`.\
+/// The day name is always the day of the week of the date.\
+/// [Learn more](https://tools.ietf.org/html/rfc5322#section-3.3)
+impl std::fmt::Display for DateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {} {} {}",
+            self.date.weekday(),
+            self.date,
+            self.time.time,
+            self.time.zone
+        )
+    }
+}
+
+/// Dates are ordered by instant, so that dates in different zones can be sorted.\
+/// Different representations of the same instant are ordered by their fields.
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.seconds_since_epoch()
+            .cmp(&other.seconds_since_epoch())
+            .then_with(|| self.fields().cmp(&other.fields()))
+    }
+}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl std::convert::TryFrom<&DateTime> for chrono::DateTime<chrono::FixedOffset> {
+    type Error = Error;
+
+    fn try_from(value: &DateTime) -> Result<Self, Self::Error> {
+        if !value.date.is_valid() {
+            return Err(Error::Unknown("This day does not exist in this month"));
+        }
+        let offset = chrono::FixedOffset::east_opt(value.time.zone.offset_seconds() as i32)
+            .ok_or(Error::Unknown("Zone out of range"))?;
+        chrono::DateTime::from_timestamp(value.timestamp(), 0)
+            .map(|date| date.with_timezone(&offset))
+            .ok_or(Error::Unknown("Date out of range"))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> std::convert::TryFrom<chrono::DateTime<Tz>> for DateTime {
+    type Error = Error;
+
+    fn try_from(value: chrono::DateTime<Tz>) -> Result<Self, Self::Error> {
+        use chrono::Offset;
+
+        let zone = Zone::from_offset_seconds(value.offset().fix().local_minus_utc() as i64)
+            .ok_or(Error::Unknown("Zone out of range"))?;
+        DateTime::from_local_timestamp(value.timestamp(), zone)
+            .ok_or(Error::Unknown("Date out of range"))
+    }
+}
+
+#[cfg(feature = "time")]
+impl std::convert::TryFrom<&DateTime> for ::time::OffsetDateTime {
+    type Error = Error;
+
+    fn try_from(value: &DateTime) -> Result<Self, Self::Error> {
+        if !value.date.is_valid() {
+            return Err(Error::Unknown("This day does not exist in this month"));
+        }
+        let offset = ::time::UtcOffset::from_whole_seconds(value.time.zone.offset_seconds() as i32)
+            .map_err(|_| Error::Unknown("Zone out of range"))?;
+        ::time::OffsetDateTime::from_unix_timestamp(value.timestamp())
+            .map(|date| date.to_offset(offset))
+            .map_err(|_| Error::Unknown("Date out of range"))
+    }
+}
+
+#[cfg(feature = "time")]
+impl std::convert::TryFrom<::time::OffsetDateTime> for DateTime {
+    type Error = Error;
+
+    fn try_from(value: ::time::OffsetDateTime) -> Result<Self, Self::Error> {
+        let zone = Zone::from_offset_seconds(value.offset().whole_seconds() as i64)
+            .ok_or(Error::Unknown("Zone out of range"))?;
+        DateTime::from_local_timestamp(value.unix_timestamp(), zone)
+            .ok_or(Error::Unknown("Date out of range"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(date: &str) -> DateTime {
+        crate::parsing::time::date_time::<false>(date.as_bytes())
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let epoch = DateTime::from_timestamp(0).unwrap();
+        assert_eq!(epoch.day_name, Some(Day::Thursday));
+        assert_eq!(
+            epoch.date,
+            Date {
+                day: 1,
+                month: Month::January,
+                year: 1970
+            }
+        );
+        assert_eq!(epoch.timestamp(), 0);
+        assert_eq!(
+            parse("Mon, 5 May 2003 18:58:34 +0200").timestamp(),
+            1052153914
+        );
+        assert_eq!(parse("29 Feb 2000 00:00 -0130").timestamp(), 951787800);
+
+        let date = parse("Mon, 5 May 2003 18:58:34 +0200");
+        assert_eq!(DateTime::from_timestamp(date.timestamp()), date.to_utc());
+        assert_eq!(
+            date.to_utc().unwrap(),
+            parse("Mon, 5 May 2003 16:58:34 +0000")
+        );
+        assert_eq!(
+            date.with_zone(Zone {
+                sign: false,
+                hour_offset: 5,
+                minute_offset: 30
+            })
+            .unwrap(),
+            parse("Mon, 5 May 2003 11:28:34 -0530")
+        );
+        let before_epoch = DateTime::from_timestamp(-1).unwrap();
+        assert_eq!(before_epoch.date.year, 1969);
+        assert_eq!(before_epoch.time.time.second, 59);
+        assert_eq!(before_epoch.timestamp(), -1);
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut dates = [
+            parse("5 May 2003 18:58:34 +0000"),
+            parse("5 May 2003 20:58:33 +0200"),
+            parse("4 May 2003 23:00:00 -0500"),
+            parse("5 May 2003 20:58:34 +0200"),
+        ];
+        dates.sort();
+        assert_eq!(dates[0], parse("4 May 2003 23:00:00 -0500"));
+        assert_eq!(dates[1], parse("5 May 2003 20:58:33 +0200"));
+        assert_eq!(dates[2], parse("5 May 2003 18:58:34 +0000"));
+        assert_eq!(dates[3], parse("5 May 2003 20:58:34 +0200"));
+        assert_eq!(dates[2].timestamp(), dates[3].timestamp());
+        assert_ne!(dates[2].cmp(&dates[3]), Ordering::Equal);
+
+        let unknown_zone = parse("5 May 2003 18:58:34 -0000");
+        let utc = parse("5 May 2003 18:58:34 +0000");
+        assert_ne!(unknown_zone, utc);
+        assert_ne!(unknown_zone.cmp(&utc), Ordering::Equal);
+        assert_eq!(unknown_zone.cmp(&unknown_zone.clone()), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(parse("Mon, 5 May 2003 18:58:34 +0000").validate().is_ok());
+        assert!(parse("Tue, 5 May 2003 18:58:34 +0000").validate().is_err());
+        assert!(parse("29 Feb 2004 18:58:34 +0000").validate().is_ok());
+        assert!(parse("29 Feb 2100 18:58:34 +0000").validate().is_err());
+        assert!(parse("31 Apr 2003 18:58:34 +0000").validate().is_err());
+        assert_eq!(
+            parse("1 Mar 2000 00:00 +0000").date.weekday(),
+            Day::Wednesday
+        );
+    }
+
+    #[test]
+    fn test_huge_year() {
+        let date = parse("1 Jan 99999999999999999 00:00 +0000");
+        assert!(date.validate().is_err());
+        assert_eq!(date.timestamp(), i64::MAX);
+        assert!(date.to_utc().is_none());
+        assert!(date > parse("1 Jan 9999 00:00 +0000"));
+        assert!(parse("31 Dec 9999 23:59:60 -2359").validate().is_ok());
+    }
+
+    #[test]
+    fn test_display() {
+        let date = parse("5 may 2003 18:58 +0000");
+        assert_eq!(date.to_string(), "Mon, 05 May 2003 18:58:00 +0000");
+
+        let zone = Zone {
+            sign: false,
+            hour_offset: 5,
+            minute_offset: 30,
+        };
+        let date = DateTime::from_timestamp_with_zone(1052161114, zone.clone()).unwrap();
+        assert_eq!(date.to_string(), "Mon, 05 May 2003 13:28:34 -0530");
+        assert_eq!(date.time.zone, zone);
+        assert_eq!(parse(&date.to_string()), date);
+
+        for timestamp in [0, 951782400, 1052161114, 4102444799] {
+            let date = DateTime::from_timestamp_with_zone(timestamp, Zone::UTC).unwrap();
+            if date.date.year >= 1990 {
+                assert_eq!(parse(&date.to_string()), date);
+            }
+            assert_eq!(date.timestamp(), timestamp);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        use std::convert::TryFrom;
+
+        let date = parse("Mon, 5 May 2003 18:58:34 +0200");
+        let chrono_date = chrono::DateTime::<chrono::FixedOffset>::try_from(&date).unwrap();
+        assert_eq!(chrono_date.to_rfc2822(), "Mon, 5 May 2003 18:58:34 +0200");
+        assert_eq!(DateTime::try_from(chrono_date).unwrap(), date);
+        assert!(chrono::DateTime::<chrono::FixedOffset>::try_from(&parse(
+            "30 Feb 2003 18:58 +0000"
+        ))
+        .is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time() {
+        use std::convert::TryFrom;
+
+        let date = parse("Mon, 5 May 2003 18:58:34 -0530");
+        let time_date = ::time::OffsetDateTime::try_from(&date).unwrap();
+        assert_eq!(time_date.unix_timestamp(), date.timestamp());
+        assert_eq!(time_date.offset().whole_minutes(), -330);
+        assert_eq!(DateTime::try_from(time_date).unwrap(), date);
+    }
+}